@@ -1,32 +1,97 @@
 use std::env;
 
+#[derive(Debug, Default)]
+pub struct CliOptions {
+    // the directory to build the graph of
+    pub directory: String,
+    // print the files that still use CommonJS
+    pub list_commonjs: bool,
+    // only draw imports with this `type` import attribute, e.g. `json`
+    pub import_type: Option<String>,
+    // draw a node per imported symbol, `file#exportName`, instead of per file
+    pub symbols: bool,
+    // print the exports that are never imported
+    pub unused_exports: bool,
+    // leave the exports tagged `@public` in their JSDoc out of the unused exports
+    pub skip_public: bool,
+    // tsconfig.json with the `baseUrl` and `paths` aliases, instead of the closest
+    // one to the directory
+    pub tsconfig: Option<String>,
+    // conditions to match in package.json `exports` and `imports` maps, on top of
+    // the default ones
+    pub conditions: Option<Vec<String>>,
+    // treat the directory as the root of a workspace and link its packages
+    pub workspace: bool,
+    // draw the installed packages and Node.js builtins that files import
+    pub external: bool,
+    // check the imported packages against the dependencies of package.json
+    pub check_dependencies: bool,
+    // print the relative imports that do not resolve to a file
+    pub unresolved: bool,
+    // exit with an error when a relative import does not resolve
+    pub strict: bool,
+}
+
 fn usage() {
-    println!("Usage: craban [-d <directory>]");
+    println!("Usage: craban [-d <directory>] [-p <tsconfig>] [--cjs] [--import-type <type>]");
+    println!("             [--symbols] [--unused-exports] [--skip-public]");
+    println!("             [--conditions <a,b,...>]");
+    println!("             [--workspace] [--external] [--check-deps]");
+    println!("             [--unresolved] [--strict]");
     println!("       craban [--help] [-h]");
     println!("Try `craban --help' for more information.");
 }
 
-pub fn parse_args() -> Result<Option<String>, ()> {
-    let mut args = env::args();
-    let directory = args.nth(1).expect("directory path flag is provided");
+pub fn parse_args() -> Result<Option<CliOptions>, ()> {
+    let mut args = env::args().skip(1);
+    let mut options = CliOptions::default();
+    let mut has_directory = false;
 
-    match directory.as_str() {
-        "-d" => {
-            let directory_path = args.next().expect("path to directory is provided");
-            Ok(Some(directory_path))
-        }
-        "-h" => {
-            usage();
-            Ok(None)
-        }
-        "--h" => {
-            usage();
-            Ok(None)
-        }
-        _ => {
-            usage();
-            eprintln!("ERROR: unknown command {directory}");
-            Err(())
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" => {
+                options.directory = args.next().expect("path to directory is provided");
+                has_directory = true;
+            }
+            "-p" => {
+                options.tsconfig = Some(args.next().expect("path to tsconfig.json is provided"));
+            }
+            "--conditions" => {
+                let conditions = args.next().expect("conditions are provided");
+                options.conditions = Some(conditions.split(',').map(String::from).collect());
+            }
+            "--cjs" => options.list_commonjs = true,
+            "--symbols" => options.symbols = true,
+            "--unused-exports" => options.unused_exports = true,
+            "--skip-public" => options.skip_public = true,
+            "--workspace" => options.workspace = true,
+            "--external" => options.external = true,
+            "--check-deps" => options.check_dependencies = true,
+            "--unresolved" => options.unresolved = true,
+            "--strict" => {
+                options.unresolved = true;
+                options.strict = true;
+            }
+            "--import-type" => {
+                options.import_type = Some(args.next().expect("import attribute type is provided"));
+            }
+            "-h" | "--h" | "--help" => {
+                usage();
+                return Ok(None);
+            }
+            _ => {
+                usage();
+                eprintln!("ERROR: unknown command {arg}");
+                return Err(());
+            }
         }
     }
+
+    if !has_directory {
+        usage();
+        eprintln!("ERROR: directory path flag is not provided");
+        return Err(());
+    }
+
+    Ok(Some(options))
 }
@@ -0,0 +1,278 @@
+use crate::json::JsonValue;
+use crate::packages::{external_module, find_package_json, types_package, PackageJson};
+use crate::structs::{ExternalKind, TsFile, TsImport, TsImportKind};
+use std::path::Path;
+
+/**
+ * Directories holding tests and development tooling rather than production code.
+ */
+static NON_PRODUCTION_DIRS: &[&str] = &["__tests__", "__mocks__", "test", "tests", "e2e"];
+
+/**
+ * Suffixes before the extension of test, story and config files, e.g.
+ * `user.test.ts`.
+ */
+static NON_PRODUCTION_SUFFIXES: &[&str] = &[".test", ".spec", ".stories", ".config"];
+
+/**
+ * A package imported by a file of the project.
+ */
+#[derive(Debug, Clone)]
+pub struct PackageImport {
+    // package name, `lodash` for `lodash/fp`
+    pub name: String,
+    // the importing file
+    pub file: String,
+    // whether the import runs in production: it is not erased as type-only and not
+    // made from a test or tooling file
+    pub production: bool,
+    // whether the import only brings in types, which an `@types` package provides
+    pub type_only: bool,
+}
+
+/**
+ * The dependencies of a `package.json` that disagree with the imports of its files.
+ */
+#[derive(Debug, Default, PartialEq)]
+pub struct DependencyCheck {
+    // packages imported without being declared, with the files importing them
+    pub undeclared: Vec<(String, Vec<String>)>,
+    // packages of `dependencies` and `peerDependencies` that no file imports
+    pub unused: Vec<String>,
+    // packages only declared in `devDependencies` that production files import,
+    // with those files
+    pub dev_only: Vec<(String, Vec<String>)>,
+}
+
+/**
+ * Returns the package `import` depends on, or None when it imports a project file,
+ * a Node.js builtin or an `#internal` specifier. Bare specifiers resolved through
+ * a tsconfig alias are project files, while those resolved into another workspace
+ * package depend on that package.
+ */
+pub fn imported_package(file: &TsFile, import: &TsImport) -> Option<String> {
+    let specifier = &import.specifier;
+    if import.kind == TsImportKind::TypeReference
+        || specifier.starts_with('.')
+        || specifier.starts_with('/')
+        || specifier.starts_with('#')
+    {
+        return None;
+    }
+
+    let (name, kind) = external_module(specifier);
+    if kind == ExternalKind::Builtin {
+        return None;
+    }
+
+    if let Some(path) = &import.resolved {
+        let package = find_package_json(path.parent()?)?;
+        let is_other_package = package.name() == Some(name.as_str())
+            && !Path::new(&file.relative_path).starts_with(&package.dir);
+        if !is_other_package {
+            return None;
+        }
+    }
+
+    Some(name)
+}
+
+/**
+ * Whether `path` is part of the production code of the package in `package_dir`,
+ * as opposed to tests, stories and config files. Only the directories within the
+ * package are looked at.
+ */
+pub fn is_production_file(package_dir: &Path, path: &Path) -> bool {
+    let path = path.strip_prefix(package_dir).unwrap_or(path);
+    let in_test_dir = path.parent().is_some_and(|dir| {
+        dir.components().any(|component| {
+            NON_PRODUCTION_DIRS.contains(&component.as_os_str().to_str().unwrap_or_default())
+        })
+    });
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or_default();
+    let stem = stem.strip_suffix(".d").unwrap_or(stem);
+
+    !in_test_dir
+        && !NON_PRODUCTION_SUFFIXES
+            .iter()
+            .any(|suffix| stem.ends_with(suffix))
+}
+
+/**
+ * Cross-checks the packages imported by the files of `package` against its
+ * `dependencies`, `devDependencies` and `peerDependencies`. A package only imported
+ * for its types counts as declared through its `@types` package, while value
+ * imports need the package itself. `@types` packages are never reported as unused.
+ */
+pub fn check_dependencies(package: &PackageJson, imports: &[PackageImport]) -> DependencyCheck {
+    let declared = |field: &str| -> Vec<&str> {
+        package
+            .json
+            .get(field)
+            .and_then(JsonValue::as_object)
+            .unwrap_or_default()
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect()
+    };
+    let dependencies = declared("dependencies");
+    let dev_dependencies = declared("devDependencies");
+    let peer_dependencies = declared("peerDependencies");
+
+    let is_declared_in = |fields: &[&Vec<&str>], import: &PackageImport| {
+        let name = import.name.as_str();
+        let types_name = types_package(name);
+        fields.iter().any(|field| {
+            field.contains(&name) || (import.type_only && field.contains(&types_name.as_str()))
+        })
+    };
+
+    let mut check = DependencyCheck::default();
+    for import in imports {
+        if package.name() == Some(import.name.as_str()) {
+            continue;
+        }
+
+        if !is_declared_in(
+            &[&dependencies, &dev_dependencies, &peer_dependencies],
+            import,
+        ) {
+            add_importing_file(&mut check.undeclared, import);
+        } else if import.production && !is_declared_in(&[&dependencies, &peer_dependencies], import)
+        {
+            add_importing_file(&mut check.dev_only, import);
+        }
+    }
+
+    check.unused = dependencies
+        .iter()
+        .chain(&peer_dependencies)
+        .filter(|name| !name.starts_with("@types/"))
+        .filter(|name| !imports.iter().any(|import| import.name == **name))
+        .map(|name| name.to_string())
+        .collect();
+    check.unused.sort();
+    check.unused.dedup();
+    check.undeclared.sort();
+    check.dev_only.sort();
+
+    check
+}
+
+fn add_importing_file(packages: &mut Vec<(String, Vec<String>)>, import: &PackageImport) {
+    let index = match packages.iter().position(|(name, _)| *name == import.name) {
+        Some(index) => index,
+        None => {
+            packages.push((import.name.clone(), Vec::new()));
+            packages.len() - 1
+        }
+    };
+
+    let files = &mut packages[index].1;
+    if !files.contains(&import.file) {
+        files.push(import.file.clone());
+        files.sort();
+    }
+}
+
+#[test]
+fn test_is_production_file() {
+    let cases = [
+        ("/app/src/index.ts", true),
+        ("/app/src/testing.ts", true),
+        ("/app/src/user.test.ts", false),
+        ("/app/src/user.spec.tsx", false),
+        ("/app/src/Button.stories.tsx", false),
+        ("/app/vite.config.ts", false),
+        ("/app/src/__tests__/user.ts", false),
+        ("/app/test/setup.ts", false),
+        ("/app/src/globals.d.ts", true),
+    ];
+
+    for (path, expected) in cases {
+        assert_eq!(
+            is_production_file(Path::new("/app"), Path::new(path)),
+            expected,
+            "{}",
+            path
+        );
+    }
+
+    // directories above the package do not count
+    let package_dir = Path::new("/tmp/test/e2e/app");
+    assert!(is_production_file(
+        package_dir,
+        &package_dir.join("src/index.ts")
+    ));
+    assert!(!is_production_file(
+        package_dir,
+        &package_dir.join("test/setup.ts")
+    ));
+}
+
+#[test]
+fn test_check_dependencies() {
+    let package = PackageJson {
+        dir: std::path::PathBuf::from("/app"),
+        json: crate::json::parse_json(
+            r#"{
+            "name": "app",
+            "dependencies": { "react": "^18.0.0", "lodash": "^4.0.0", "left-pad": "^1.0.0" },
+            "devDependencies": { "jest": "^29.0.0", "@types/lodash": "^4.0.0", "@types/scope__typed": "^1.0.0", "@types/express": "^4.0.0" },
+            "peerDependencies": { "react-dom": "^18.0.0" }
+        }"#,
+        )
+        .unwrap(),
+    };
+
+    let import = |name: &str, file: &str, production: bool| PackageImport {
+        name: name.to_string(),
+        file: file.to_string(),
+        production,
+        type_only: false,
+    };
+    let type_import = |name: &str, file: &str| PackageImport {
+        type_only: true,
+        ..import(name, file, false)
+    };
+    let imports = [
+        import("react", "/src/App.tsx", true),
+        import("react-dom", "/src/index.tsx", true),
+        import("lodash", "/src/utils.ts", true),
+        import("axios", "/src/api.ts", true),
+        import("axios", "/src/client.ts", true),
+        import("axios", "/src/api.ts", true),
+        import("jest", "/src/App.test.tsx", false),
+        import("jest", "/src/setup.ts", true),
+        type_import("@scope/typed", "/src/types.ts"),
+        type_import("express", "/src/types.ts"),
+        // `@types/express` declares the types of express, not the package itself
+        import("express", "/src/server.ts", true),
+        import("express", "/src/server.test.ts", false),
+        import("app", "/src/self.ts", true),
+    ];
+
+    assert_eq!(
+        check_dependencies(&package, &imports),
+        DependencyCheck {
+            undeclared: vec![
+                (
+                    "axios".to_string(),
+                    vec!["/src/api.ts".to_string(), "/src/client.ts".to_string()]
+                ),
+                (
+                    "express".to_string(),
+                    vec![
+                        "/src/server.test.ts".to_string(),
+                        "/src/server.ts".to_string()
+                    ]
+                ),
+            ],
+            unused: vec!["left-pad".to_string()],
+            dev_only: vec![("jest".to_string(), vec!["/src/setup.ts".to_string()])],
+        }
+    );
+}
@@ -0,0 +1,160 @@
+use crate::structs::{TsExport, TsFile, TsImport, TsImportKind};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/**
+ * Finds the exports that no file of the project imports, following re-exports
+ * through barrel files: `export { a } from './a'` only uses `a` of `./a` once the
+ * re-exported `a` is itself imported.
+ *
+ * `file_key` names a file and `import_key` names the file an import refers to, the
+ * same way, so imports can be matched with the files they import.
+ */
+pub fn find_unused_exports(
+    files: &[TsFile],
+    file_key: impl Fn(&TsFile) -> String,
+    import_key: impl Fn(&TsFile, &TsImport) -> String,
+) -> Vec<(&TsFile, &TsExport)> {
+    // re-exports of each file, with the key of the file they re-export from
+    let mut reexports: HashMap<String, Vec<(String, &TsImport)>> = HashMap::new();
+    // names of a file imported somewhere, `*` when the whole module is used
+    let mut used: HashSet<(String, String)> = HashSet::new();
+    let mut queue: VecDeque<(String, String)> = VecDeque::new();
+
+    for file in files {
+        for import in &file.imports {
+            let imported_file = import_key(file, import);
+
+            if import.kind == TsImportKind::ReExport {
+                reexports
+                    .entry(file_key(file))
+                    .or_default()
+                    .push((imported_file, import));
+            } else {
+                for name in used_names(import) {
+                    queue.push_back((imported_file.clone(), name.to_string()));
+                }
+            }
+        }
+    }
+
+    while let Some((file, name)) = queue.pop_front() {
+        if !used.insert((file.clone(), name.clone())) {
+            continue;
+        }
+
+        for (imported_file, reexport) in reexports.get(&file).into_iter().flatten() {
+            for (imported, local) in reexport.imported_names() {
+                let forwarded = if name == "*" {
+                    Some(imported)
+                } else if reexport.export_all {
+                    // `export *` forwards every name but the default export
+                    (name != "default").then_some(name.as_str())
+                } else {
+                    (local == name).then_some(imported)
+                };
+
+                if let Some(forwarded) = forwarded {
+                    queue.push_back((imported_file.clone(), forwarded.to_string()));
+                }
+            }
+        }
+    }
+
+    let mut unused = Vec::new();
+    for file in files {
+        let key = file_key(file);
+        if used.contains(&(key.clone(), "*".to_string())) {
+            continue;
+        }
+
+        for export in &file.exports {
+            if !used.contains(&(key.clone(), export.name.clone())) {
+                unused.push((file, export));
+            }
+        }
+    }
+
+    unused
+}
+
+/**
+ * The names of the imported module that `import` uses. Dynamic imports without
+ * bindings, `require()` calls and `import x = require()` get the whole module,
+ * while side effect imports and `/// <reference />` directives use none of its
+ * exports.
+ */
+fn used_names(import: &TsImport) -> Vec<&str> {
+    let names: Vec<&str> = import
+        .imported_names()
+        .into_iter()
+        .map(|(imported, _)| imported)
+        .collect();
+
+    match import.kind {
+        TsImportKind::Dynamic if names.is_empty() => vec!["*"],
+        TsImportKind::Require => vec!["*"],
+        TsImportKind::TypeReference => Vec::new(),
+        _ => names,
+    }
+}
+
+#[cfg(test)]
+fn unused_export_names(sources: &[(&str, &str)]) -> Vec<String> {
+    let files: Vec<TsFile> = sources
+        .iter()
+        .map(|(path, data)| {
+            crate::parser::parse_ts_file(data, false, path.to_string(), path.to_string())
+        })
+        .collect();
+
+    find_unused_exports(
+        &files,
+        |file| file.relative_path.clone(),
+        |_, import| format!("/{}.ts", import.source.trim_start_matches("./")),
+    )
+    .into_iter()
+    .map(|(file, export)| format!("{}#{}", file.relative_path, export.name))
+    .collect()
+}
+
+#[test]
+fn test_unused_exports() {
+    let unused = unused_export_names(&[
+        ("/a.ts", "export const a = 1; export const unusedA = 2;"),
+        ("/b.ts", "export const b = 1; export default b;"),
+        ("/c.ts", "export function c() {} export const unusedC = 1;"),
+        ("/lazy.ts", "export const lazy = 1;"),
+        ("/cjs.ts", "export = 1;"),
+        (
+            "/lib.ts",
+            "export function helper() {} export const other = 1;",
+        ),
+        (
+            "/barrel.ts",
+            "export { a } from './a'; export * from './b'; export * as c from './c';",
+        ),
+        (
+            "/index.ts",
+            "
+            import { a, b, c as namespace } from './barrel';
+            import cjs = require('./cjs');
+            import lib = require('./lib');
+            lib.helper();
+            const lazy = () => import('./lazy');
+            /** @public */
+            export const api = 1;
+            export const unusedIndex = 2;
+            ",
+        ),
+    ]);
+
+    assert_eq!(
+        unused,
+        vec![
+            "/a.ts#unusedA",
+            "/b.ts#default",
+            "/index.ts#api",
+            "/index.ts#unusedIndex"
+        ]
+    );
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/**
+ * A directory of files for tests, in the system temp directory, that is removed
+ * when dropped, so that a failing test does not leave it behind.
+ */
+pub struct TestDir {
+    // canonical path of the directory, as the resolver returns files
+    root: PathBuf,
+}
+
+impl TestDir {
+    /**
+     * Creates an empty `craban-<name>-<pid>` directory, removing the one an
+     * interrupted run may have left.
+     */
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("craban-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        TestDir {
+            root: fs::canonicalize(root).unwrap(),
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+
+    /**
+     * Writes `data` to `path` within the directory, creating its parent directories.
+     */
+    pub fn write(&self, path: &str, data: &str) {
+        let path = self.root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, data).unwrap();
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
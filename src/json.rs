@@ -0,0 +1,222 @@
+use crate::lexer::{Lexer, TokenKind};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    // members in the order they are written
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    /**
+     * Returns the member `key` of an object, or None for every other value.
+     */
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(members) => members
+                .iter()
+                .find(|(member, _)| member == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, JsonValue)]> {
+        match self {
+            JsonValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Parses a JSON document such as a `package.json`, using the TypeScript lexer for
+ * its tokens. The comments and trailing commas of JSONC, as written in
+ * `tsconfig.json`, are accepted. Returns None when `data` is not valid JSON.
+ */
+pub fn parse_json(data: &str) -> Option<JsonValue> {
+    let mut lexer = Lexer::new(data, data.len());
+
+    let value = parse_value(&mut lexer)?;
+    match lexer.next_significant() {
+        None => Some(value),
+        Some(_) => None,
+    }
+}
+
+fn parse_value(lexer: &mut Lexer) -> Option<JsonValue> {
+    let token = lexer.next_significant()?;
+
+    match token.token_kind {
+        TokenKind::TokenOpenCurly => parse_object(lexer),
+        TokenKind::TokenOpenBracket => parse_array(lexer),
+        TokenKind::TokenString => Some(JsonValue::String(token.value()?)),
+        TokenKind::TokenNumber => Some(JsonValue::Number(token.value()?.parse().ok()?)),
+        TokenKind::TokenMinus => {
+            let number = lexer.next_significant()?;
+            if number.token_kind != TokenKind::TokenNumber {
+                return None;
+            }
+            Some(JsonValue::Number(-number.value()?.parse::<f64>().ok()?))
+        }
+        TokenKind::TokenKeyword => match token.get_text().as_str() {
+            "true" => Some(JsonValue::Bool(true)),
+            "false" => Some(JsonValue::Bool(false)),
+            "null" => Some(JsonValue::Null),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/**
+ * Parses the members of an object after its opening `{` up to and including the
+ * closing `}`.
+ */
+fn parse_object(lexer: &mut Lexer) -> Option<JsonValue> {
+    let mut members = Vec::new();
+
+    loop {
+        let token = lexer.next_significant()?;
+        match token.token_kind {
+            TokenKind::TokenCloseCurly => break,
+            TokenKind::TokenString => {}
+            _ => return None,
+        }
+        let key = token.value()?;
+
+        if lexer.next_significant()?.token_kind != TokenKind::TokenColon {
+            return None;
+        }
+        members.push((key, parse_value(lexer)?));
+
+        match lexer.next_significant()?.token_kind {
+            TokenKind::TokenComma => {}
+            TokenKind::TokenCloseCurly => break,
+            _ => return None,
+        }
+    }
+
+    Some(JsonValue::Object(members))
+}
+
+/**
+ * Parses the elements of an array after its opening `[` up to and including the
+ * closing `]`.
+ */
+fn parse_array(lexer: &mut Lexer) -> Option<JsonValue> {
+    let mut elements = Vec::new();
+
+    loop {
+        if lexer.peek_is(0, TokenKind::TokenCloseBracket) {
+            lexer.next_significant();
+            break;
+        }
+        elements.push(parse_value(lexer)?);
+
+        match lexer.next_significant()?.token_kind {
+            TokenKind::TokenComma => {}
+            TokenKind::TokenCloseBracket => break,
+            _ => return None,
+        }
+    }
+
+    Some(JsonValue::Array(elements))
+}
+
+#[test]
+fn test_parse_json() {
+    let value = parse_json(
+        r#"{
+        "name": "@scope/app",
+        "version": "1.0.0",
+        "private": true,
+        "main": "./dist/index.js",
+        "files": ["dist", "src"],
+        "engines": { "node": ">=18" },
+        "nothing": null,
+        "offset": -1.5,
+        "escaped": "a\"bA",
+        "empty": {},
+        "none": []
+    }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value.get("name").and_then(JsonValue::as_str),
+        Some("@scope/app")
+    );
+    assert_eq!(value.get("private"), Some(&JsonValue::Bool(true)));
+    assert_eq!(
+        value.get("files"),
+        Some(&JsonValue::Array(vec![
+            JsonValue::String("dist".to_string()),
+            JsonValue::String("src".to_string()),
+        ]))
+    );
+    assert_eq!(
+        value
+            .get("engines")
+            .and_then(|engines| engines.get("node"))
+            .and_then(JsonValue::as_str),
+        Some(">=18")
+    );
+    assert_eq!(value.get("nothing"), Some(&JsonValue::Null));
+    assert_eq!(value.get("offset"), Some(&JsonValue::Number(-1.5)));
+    assert_eq!(
+        value.get("escaped").and_then(JsonValue::as_str),
+        Some("a\"bA")
+    );
+    assert_eq!(value.get("empty"), Some(&JsonValue::Object(Vec::new())));
+    assert_eq!(value.get("none"), Some(&JsonValue::Array(Vec::new())));
+    assert_eq!(value.get("missing"), None);
+
+    assert_eq!(parse_json("{ \"a\": 1 } }"), None);
+    assert_eq!(parse_json("{ \"a\" 1 }"), None);
+    assert_eq!(parse_json("{ a: 1 }"), None);
+    assert_eq!(parse_json("[1,,]"), None);
+}
+
+#[test]
+fn test_parse_jsonc() {
+    let value = parse_json(
+        r#"{
+        // base options
+        "compilerOptions": {
+            /* resolution */
+            "baseUrl": ".",
+            "paths": { "@app/*": ["src/*",], },
+        },
+    }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        value
+            .get("compilerOptions")
+            .and_then(|options| options.get("paths"))
+            .and_then(|paths| paths.get("@app/*")),
+        Some(&JsonValue::Array(vec![JsonValue::String(
+            "src/*".to_string()
+        )]))
+    );
+}
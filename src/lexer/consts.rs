@@ -1,5 +1,3 @@
-use std::fmt::Display;
-
 pub static TYPESCRIPT_KEYWORDS: &[&str] = &[
     "abstract",
     "any",
@@ -82,19 +80,97 @@ pub struct LiteralToken<'a> {
 }
 
 #[derive(Debug, Clone, PartialEq)]
+#[allow(clippy::enum_variant_names)]
 pub enum TokenKind {
     TokenKeyword,
     TokenComment,
+    TokenBlockComment,
+    TokenDocComment,
+    TokenHashbang,
     TokenSymbol,
-    TokenStar,
+    TokenString,
+    TokenTemplate,
+    TokenNumber,
+    TokenRegex,
+    TokenJsxText,
     TokenInvalid,
     TokenOpenCurly,
     TokenCloseCurly,
     TokenOpenParen,
     TokenCloseParen,
     TokenComma,
+    TokenOpenBracket,
+    TokenCloseBracket,
+    TokenDot,
+    TokenEllipsis,
+    TokenSemicolon,
+    TokenColon,
+    TokenQuestion,
+    TokenOptionalChain,
+    TokenQuestionQuestion,
+    TokenArrow,
+    TokenAt,
+    TokenHash,
+    TokenLt,
+    TokenGt,
+    TokenLtEq,
+    TokenGtEq,
+    TokenEqEq,
+    TokenNotEq,
+    TokenEqEqEq,
+    TokenNotEqEq,
+    TokenPlus,
+    TokenMinus,
+    TokenStar,
+    TokenSlash,
+    TokenPercent,
+    TokenStarStar,
+    TokenPlusPlus,
+    TokenMinusMinus,
+    TokenShl,
+    TokenShr,
+    TokenUShr,
+    TokenAmp,
+    TokenPipe,
+    TokenCaret,
+    TokenBang,
+    TokenTilde,
+    TokenAmpAmp,
+    TokenPipePipe,
+    TokenEq,
+    TokenPlusEq,
+    TokenMinusEq,
+    TokenStarEq,
+    TokenSlashEq,
+    TokenPercentEq,
+    TokenStarStarEq,
+    TokenShlEq,
+    TokenShrEq,
+    TokenUShrEq,
+    TokenAmpEq,
+    TokenPipeEq,
+    TokenCaretEq,
+    TokenAmpAmpEq,
+    TokenPipePipeEq,
+    TokenQuestionQuestionEq,
+}
+
+impl TokenKind {
+    pub fn is_comment(&self) -> bool {
+        matches!(
+            self,
+            TokenKind::TokenComment
+                | TokenKind::TokenBlockComment
+                | TokenKind::TokenDocComment
+                | TokenKind::TokenHashbang
+        )
+    }
 }
 
+/**
+ * ECMAScript and TypeScript punctuators. The lexer picks the longest entry that
+ * matches at the cursor, so the order of this table does not matter.
+ */
 pub static LITERAL_TOKENS: &[LiteralToken] = &[
     LiteralToken {
         text: "{",
@@ -116,4 +192,220 @@ pub static LITERAL_TOKENS: &[LiteralToken] = &[
         text: ",",
         token_kind: TokenKind::TokenComma,
     },
+    LiteralToken {
+        text: "[",
+        token_kind: TokenKind::TokenOpenBracket,
+    },
+    LiteralToken {
+        text: "]",
+        token_kind: TokenKind::TokenCloseBracket,
+    },
+    LiteralToken {
+        text: ".",
+        token_kind: TokenKind::TokenDot,
+    },
+    LiteralToken {
+        text: "...",
+        token_kind: TokenKind::TokenEllipsis,
+    },
+    LiteralToken {
+        text: ";",
+        token_kind: TokenKind::TokenSemicolon,
+    },
+    LiteralToken {
+        text: ":",
+        token_kind: TokenKind::TokenColon,
+    },
+    LiteralToken {
+        text: "?",
+        token_kind: TokenKind::TokenQuestion,
+    },
+    LiteralToken {
+        text: "?.",
+        token_kind: TokenKind::TokenOptionalChain,
+    },
+    LiteralToken {
+        text: "??",
+        token_kind: TokenKind::TokenQuestionQuestion,
+    },
+    LiteralToken {
+        text: "=>",
+        token_kind: TokenKind::TokenArrow,
+    },
+    LiteralToken {
+        text: "@",
+        token_kind: TokenKind::TokenAt,
+    },
+    LiteralToken {
+        text: "#",
+        token_kind: TokenKind::TokenHash,
+    },
+    LiteralToken {
+        text: "<",
+        token_kind: TokenKind::TokenLt,
+    },
+    LiteralToken {
+        text: ">",
+        token_kind: TokenKind::TokenGt,
+    },
+    LiteralToken {
+        text: "<=",
+        token_kind: TokenKind::TokenLtEq,
+    },
+    LiteralToken {
+        text: ">=",
+        token_kind: TokenKind::TokenGtEq,
+    },
+    LiteralToken {
+        text: "==",
+        token_kind: TokenKind::TokenEqEq,
+    },
+    LiteralToken {
+        text: "!=",
+        token_kind: TokenKind::TokenNotEq,
+    },
+    LiteralToken {
+        text: "===",
+        token_kind: TokenKind::TokenEqEqEq,
+    },
+    LiteralToken {
+        text: "!==",
+        token_kind: TokenKind::TokenNotEqEq,
+    },
+    LiteralToken {
+        text: "+",
+        token_kind: TokenKind::TokenPlus,
+    },
+    LiteralToken {
+        text: "-",
+        token_kind: TokenKind::TokenMinus,
+    },
+    LiteralToken {
+        text: "*",
+        token_kind: TokenKind::TokenStar,
+    },
+    LiteralToken {
+        text: "/",
+        token_kind: TokenKind::TokenSlash,
+    },
+    LiteralToken {
+        text: "%",
+        token_kind: TokenKind::TokenPercent,
+    },
+    LiteralToken {
+        text: "**",
+        token_kind: TokenKind::TokenStarStar,
+    },
+    LiteralToken {
+        text: "++",
+        token_kind: TokenKind::TokenPlusPlus,
+    },
+    LiteralToken {
+        text: "--",
+        token_kind: TokenKind::TokenMinusMinus,
+    },
+    LiteralToken {
+        text: "<<",
+        token_kind: TokenKind::TokenShl,
+    },
+    LiteralToken {
+        text: ">>",
+        token_kind: TokenKind::TokenShr,
+    },
+    LiteralToken {
+        text: ">>>",
+        token_kind: TokenKind::TokenUShr,
+    },
+    LiteralToken {
+        text: "&",
+        token_kind: TokenKind::TokenAmp,
+    },
+    LiteralToken {
+        text: "|",
+        token_kind: TokenKind::TokenPipe,
+    },
+    LiteralToken {
+        text: "^",
+        token_kind: TokenKind::TokenCaret,
+    },
+    LiteralToken {
+        text: "!",
+        token_kind: TokenKind::TokenBang,
+    },
+    LiteralToken {
+        text: "~",
+        token_kind: TokenKind::TokenTilde,
+    },
+    LiteralToken {
+        text: "&&",
+        token_kind: TokenKind::TokenAmpAmp,
+    },
+    LiteralToken {
+        text: "||",
+        token_kind: TokenKind::TokenPipePipe,
+    },
+    LiteralToken {
+        text: "=",
+        token_kind: TokenKind::TokenEq,
+    },
+    LiteralToken {
+        text: "+=",
+        token_kind: TokenKind::TokenPlusEq,
+    },
+    LiteralToken {
+        text: "-=",
+        token_kind: TokenKind::TokenMinusEq,
+    },
+    LiteralToken {
+        text: "*=",
+        token_kind: TokenKind::TokenStarEq,
+    },
+    LiteralToken {
+        text: "/=",
+        token_kind: TokenKind::TokenSlashEq,
+    },
+    LiteralToken {
+        text: "%=",
+        token_kind: TokenKind::TokenPercentEq,
+    },
+    LiteralToken {
+        text: "**=",
+        token_kind: TokenKind::TokenStarStarEq,
+    },
+    LiteralToken {
+        text: "<<=",
+        token_kind: TokenKind::TokenShlEq,
+    },
+    LiteralToken {
+        text: ">>=",
+        token_kind: TokenKind::TokenShrEq,
+    },
+    LiteralToken {
+        text: ">>>=",
+        token_kind: TokenKind::TokenUShrEq,
+    },
+    LiteralToken {
+        text: "&=",
+        token_kind: TokenKind::TokenAmpEq,
+    },
+    LiteralToken {
+        text: "|=",
+        token_kind: TokenKind::TokenPipeEq,
+    },
+    LiteralToken {
+        text: "^=",
+        token_kind: TokenKind::TokenCaretEq,
+    },
+    LiteralToken {
+        text: "&&=",
+        token_kind: TokenKind::TokenAmpAmpEq,
+    },
+    LiteralToken {
+        text: "||=",
+        token_kind: TokenKind::TokenPipePipeEq,
+    },
+    LiteralToken {
+        text: "??=",
+        token_kind: TokenKind::TokenQuestionQuestionEq,
+    },
 ];
@@ -1,24 +1,46 @@
 mod consts;
+use std::collections::VecDeque;
 use std::fmt;
 
-use consts::{TokenKind, LITERAL_TOKENS, TYPESCRIPT_KEYWORDS};
+pub use consts::TokenKind;
+use consts::{LITERAL_TOKENS, TYPESCRIPT_KEYWORDS};
+
+/**
+ * Location of a token in the source: `start` and `end` are byte offsets, `line` and
+ * `column` are 1-based and point at the first character of the token.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Token<'a> {
     pub token_kind: TokenKind,
     pub text: &'a str,
     pub text_len: usize,
+    pub span: Span,
 }
 
 impl fmt::Display for Token<'_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        println!(
-            "Token {{\n token_kind: TokenKind::{:#?},\n text: \"{}\",\n text_len: {}\n}}",
+        write!(
+            f,
+            "Token {{\n token_kind: TokenKind::{:#?},\n text: \"{}\",\n text_len: {},\n span: {}\n}}",
             self.token_kind,
             self.get_text(),
-            self.text_len
-        );
-        Ok(())
+            self.text_len,
+            self.span
+        )
     }
 }
 
@@ -30,20 +52,96 @@ pub struct Lexer<'a> {
     pub line: usize,
     // begining of the line
     pub bol: usize,
+    // characters (not bytes) between the begining of the line and the cursor
+    column: usize,
+    // last token that was not a comment, used to tell a regex literal from a division
+    prev_token: Option<Token<'a>>,
+    // tokens already lexed by `peek`/`peek_nth` but not yet returned by `next`;
+    // `cursor`, `line` and `bol` point past the last of them
+    lookahead: VecDeque<Token<'a>>,
+    // whether `<` in expression position starts a JSX element (.tsx/.jsx files)
+    jsx: bool,
+    // JSX constructs the cursor is nested in, innermost last
+    jsx_stack: Vec<JsxContext>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JsxTag {
+    // `<div ...>`
+    Opening,
+    // `</div>`
+    Closing,
+    // `<div ... />`, once the `/` has been seen
+    SelfClosing,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum JsxContext {
+    // between `<` and `>` of a tag: names, attributes and attribute values
+    Tag(JsxTag),
+    // between an opening and a closing tag: text, child elements and `{}`
+    Children,
+    // inside `{}` in a tag or in children, lexed as regular code
+    Expression { braces_count: usize },
+}
+
+/**
+ * Saved lexer position created by `Lexer::checkpoint`. Passing it to `Lexer::rewind`
+ * makes the lexer return the same tokens again from that position.
+ */
+#[derive(Debug, Clone)]
+pub struct Checkpoint<'a> {
+    lexer: Lexer<'a>,
 }
 
 fn is_symbol_start(s: char) -> bool {
-    return s.is_alphabetic() || s == '_';
+    s.is_alphabetic() || s == '_' || s == '$'
 }
 
 fn is_symbol(s: char) -> bool {
-    return s.is_alphanumeric() || s == '_';
+    s.is_alphanumeric() || s == '_' || s == '$'
 }
 
 fn is_space(s: char) -> bool {
     s.is_whitespace()
 }
 
+fn is_number_start(s: char) -> bool {
+    s.is_ascii_digit()
+}
+
+/**
+ * Checks whether `rest`, the text after a `<`, is a list of type parameters
+ * followed by parameters and an arrow, `T, U>(a: T, b: U) => ...`, which is a
+ * function type or arrow function rather than a JSX element.
+ */
+fn is_type_parameter_list(rest: &str) -> bool {
+    let Some((parameters, after)) = rest.split_once('>') else {
+        return false;
+    };
+    let is_names = parameters.split(',').all(|parameter| {
+        let parameter = parameter.trim();
+        parameter.chars().next().is_some_and(is_symbol_start) && parameter.chars().all(is_symbol)
+    });
+    let Some(after) = after.trim_start().strip_prefix('(').filter(|_| is_names) else {
+        return false;
+    };
+
+    let mut parens_count = 0;
+    for (index, c) in after.char_indices() {
+        match c {
+            '(' => parens_count += 1,
+            ')' if parens_count == 0 => {
+                return after[index + 1..].trim_start().starts_with("=>");
+            }
+            ')' => parens_count -= 1,
+            _ => {}
+        }
+    }
+
+    false
+}
+
 impl Token<'_> {
     pub fn get_text(&self) -> String {
         if self.text_len == 1 {
@@ -51,21 +149,324 @@ impl Token<'_> {
         }
         self.text[..self.text_len].to_string()
     }
+
+    /**
+     * Returns the value a literal token evaluates to:
+     * - strings and templates without substitutions are unescaped
+     * - numbers are returned without numeric separators
+     *
+     * Every other token, and templates containing `${}`, have no static value.
+     */
+    pub fn value(&self) -> Option<String> {
+        let text = &self.text[..self.text_len];
+
+        match self.token_kind {
+            TokenKind::TokenString => Some(unescape(strip_delimiters(text))),
+            TokenKind::TokenTemplate if !has_substitutions(text) => {
+                Some(unescape(strip_delimiters(text)))
+            }
+            TokenKind::TokenNumber => Some(text.replace('_', "")),
+            _ => None,
+        }
+    }
+
+    /**
+     * Returns the text of a `/** */` doc comment token, or None for every other token.
+     */
+    pub fn doc_text(&self) -> Option<String> {
+        match self.token_kind {
+            TokenKind::TokenDocComment => Some(doc_comment_text(&self.text[..self.text_len])),
+            _ => None,
+        }
+    }
+}
+
+/**
+ * Returns the text of a JSDoc comment without the comment delimiters and the
+ * leading `*` of each line, so tags such as `@deprecated` start their own line.
+ */
+fn doc_comment_text(comment: &str) -> String {
+    let body = comment.strip_prefix("/**").unwrap_or(comment);
+    let body = body.strip_suffix("*/").unwrap_or(body);
+
+    body.lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .skip_while(|line| line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim_end()
+        .to_string()
+}
+
+/**
+ * Removes the opening and (when the literal is terminated) closing quote of a
+ * string or template literal.
+ */
+fn strip_delimiters(text: &str) -> &str {
+    let quote = &text[..1];
+    let inner = &text[1..];
+
+    match inner.strip_suffix(quote) {
+        // the closing quote is escaped when preceded by an odd number of backslashes
+        Some(stripped) if stripped.chars().rev().take_while(|c| *c == '\\').count() % 2 == 0 => {
+            stripped
+        }
+        _ => inner,
+    }
+}
+
+fn has_substitutions(template: &str) -> bool {
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '$' if chars.clone().next() == Some('{') => return true,
+            _ => {}
+        }
+    }
+
+    false
 }
 
-impl Lexer<'_> {
-    pub fn new(content: &str, length: usize) -> Lexer {
+fn unescape(text: &str) -> String {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        let Some(escaped) = chars.next() else {
+            break;
+        };
+
+        match escaped {
+            'n' => unescaped.push('\n'),
+            't' => unescaped.push('\t'),
+            'r' => unescaped.push('\r'),
+            'b' => unescaped.push('\u{8}'),
+            'f' => unescaped.push('\u{c}'),
+            'v' => unescaped.push('\u{b}'),
+            '0' => unescaped.push('\0'),
+            // line continuation
+            '\r' => {
+                chars.next_if_eq(&'\n');
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => {}
+            'x' => {
+                let hex: String = chars.by_ref().take(2).collect();
+                push_code_point(&mut unescaped, &hex);
+            }
+            'u' if chars.next_if_eq(&'{').is_some() => {
+                let hex: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                push_code_point(&mut unescaped, &hex);
+            }
+            'u' => {
+                let hex: String = chars.by_ref().take(4).collect();
+                push_code_point(&mut unescaped, &hex);
+            }
+            other => unescaped.push(other),
+        }
+    }
+
+    unescaped
+}
+
+fn push_code_point(s: &mut String, hex: &str) {
+    match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+        Some(c) => s.push(c),
+        None => s.push(char::REPLACEMENT_CHARACTER),
+    }
+}
+
+/**
+ * Keywords after which an expression is expected, so a `/` that follows them
+ * starts a regex literal. Contextual ones such as `await` are lexed as symbols.
+ */
+static REGEX_PRECEDING_KEYWORDS: &[&str] = &[
+    "await",
+    "case",
+    "delete",
+    "do",
+    "else",
+    "extends",
+    "in",
+    "instanceof",
+    "new",
+    "of",
+    "return",
+    "throw",
+    "typeof",
+    "void",
+    "yield",
+];
+
+impl<'a> Lexer<'a> {
+    pub fn new(content: &'a str, length: usize) -> Lexer<'a> {
         Lexer {
             content,
             content_len: length,
             bol: 0,
             cursor: 0,
             line: 0,
+            column: 0,
+            prev_token: None,
+            lookahead: VecDeque::new(),
+            jsx: false,
+            jsx_stack: Vec::new(),
+        }
+    }
+
+    /**
+     * Enables JSX lexing, used for .tsx and .jsx files. Elements produce `<`, `>` and
+     * `/` tokens, tag and attribute names are `TokenSymbol`s, attribute values are
+     * `TokenString`s and text children are `TokenJsxText`s.
+     */
+    pub fn with_jsx(mut self, jsx: bool) -> Lexer<'a> {
+        self.jsx = jsx;
+        self
+    }
+
+    /**
+     * Returns the next token without consuming it.
+     */
+    pub fn peek(&mut self) -> Option<&Token<'a>> {
+        self.peek_nth(0)
+    }
+
+    /**
+     * Returns the token `n` positions ahead of the next one (`peek_nth(0)` is the
+     * next token) without consuming anything.
+     */
+    pub fn peek_nth(&mut self, n: usize) -> Option<&Token<'a>> {
+        while self.lookahead.len() <= n {
+            let token = self._lex()?;
+            self.lookahead.push_back(token);
+        }
+
+        self.lookahead.get(n)
+    }
+
+    /**
+     * Returns the next token that is not a comment, consuming the comments before
+     * it.
+     */
+    pub fn next_significant(&mut self) -> Option<Token<'a>> {
+        self.find(|token| !token.token_kind.is_comment())
+    }
+
+    /**
+     * Returns the `n`th upcoming token that is not a comment without consuming it.
+     */
+    pub fn peek_significant(&mut self, n: usize) -> Option<Token<'a>> {
+        let mut seen = 0;
+        let mut i = 0;
+
+        while let Some(token) = self.peek_nth(i) {
+            if !token.token_kind.is_comment() {
+                if seen == n {
+                    return Some(token.clone());
+                }
+                seen += 1;
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    /**
+     * Checks the kind of the `n`th upcoming token that is not a comment.
+     */
+    pub fn peek_is(&mut self, n: usize, kind: TokenKind) -> bool {
+        self.peek_significant(n)
+            .is_some_and(|token| token.token_kind == kind)
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint<'a> {
+        Checkpoint {
+            lexer: self.clone(),
         }
     }
 
-    fn _curr_cursor_char(&mut self) -> char {
-        return self.content.chars().nth(self.cursor).unwrap();
+    pub fn rewind(&mut self, checkpoint: Checkpoint<'a>) {
+        *self = checkpoint.lexer;
+    }
+
+    /**
+     * Decides whether a `/` at the cursor starts a regex literal or is a division,
+     * based on the previous significant token. A regex can only appear where an
+     * expression is expected, e.g. at the start of the file, after an operator or
+     * an opening bracket, or after keywords like `return`.
+     */
+    fn _regex_allowed(&self) -> bool {
+        let Some(prev) = &self.prev_token else {
+            return true;
+        };
+
+        match prev.token_kind {
+            TokenKind::TokenString
+            | TokenKind::TokenTemplate
+            | TokenKind::TokenNumber
+            | TokenKind::TokenRegex
+            | TokenKind::TokenCloseParen
+            | TokenKind::TokenCloseBracket
+            | TokenKind::TokenPlusPlus
+            | TokenKind::TokenMinusMinus => false,
+            TokenKind::TokenKeyword | TokenKind::TokenSymbol => {
+                REGEX_PRECEDING_KEYWORDS.contains(&&prev.text[..prev.text_len])
+            }
+            _ => true,
+        }
+    }
+
+    /**
+     * Consumes a regex literal, including its flags, starting at the opening `/`.
+     * A `/` inside a character class does not end the literal. An unterminated
+     * regex ends at the line break.
+     */
+    fn _chop_regex(&mut self) {
+        self._chop_char().unwrap();
+        let mut in_class = false;
+
+        while let Some(c) = self._peek_char(0) {
+            if c == '\n' {
+                return;
+            }
+            self._chop_char().unwrap();
+            match c {
+                '\\' if self._peek_char(0).is_some_and(|c| c != '\n') => {
+                    self._chop_char().unwrap();
+                }
+                '[' => in_class = true,
+                ']' => in_class = false,
+                '/' if !in_class => break,
+                _ => {}
+            }
+        }
+
+        while self._peek_char(0).is_some_and(is_symbol) {
+            self._chop_char().unwrap();
+        }
+    }
+
+    // `cursor` is a byte offset that always sits on a char boundary, so decoding
+    // the char at the cursor is constant time
+    fn _curr_cursor_char(&self) -> char {
+        self._peek_char(0).unwrap()
+    }
+
+    fn _peek_char(&self, offset: usize) -> Option<char> {
+        self.content[self.cursor..].chars().nth(offset)
     }
 
     fn _chop_char(&mut self) -> Result<char, ()> {
@@ -75,102 +476,468 @@ impl Lexer<'_> {
 
         let x = self._curr_cursor_char();
 
-        self.cursor += 1;
+        self.cursor += x.len_utf8();
+        self.column += 1;
 
         if x == '\n' {
             self.line += 1;
             self.bol = self.cursor;
+            self.column = 0;
         }
 
         Ok(x)
     }
 
+    /**
+     * Moves the cursor past `text`, which must be what the content holds at the
+     * cursor and must not contain a line break.
+     */
+    fn _chop_str(&mut self, text: &str) {
+        self.cursor += text.len();
+        self.column += text.chars().count();
+    }
+
     fn _trim(&mut self) {
         while self.cursor < self.content_len && is_space(self._curr_cursor_char()) {
             self._chop_char().unwrap();
         }
     }
 
-    fn _starts_with(&mut self, prefix: &str) -> bool {
-        let pref_len = prefix.len();
+    fn _starts_with(&self, prefix: &str) -> bool {
+        self.content[self.cursor..].starts_with(prefix)
+    }
 
-        if pref_len == 0 {
-            return true;
+    /**
+     * Consumes the rest of the current line, including the line break.
+     */
+    fn _chop_line(&mut self) {
+        while self.cursor < self.content_len && self._curr_cursor_char() != '\n' {
+            self._chop_char().unwrap();
+        }
+        if self.cursor < self.content_len {
+            self._chop_char().unwrap();
         }
+    }
 
-        if self.cursor + pref_len - 1 >= self.content_len {
-            return false;
+    /**
+     * Consumes a string literal starting at the opening quote. An unterminated
+     * string ends at the line break.
+     */
+    fn _chop_string(&mut self) {
+        let quote = self._chop_char().unwrap();
+
+        while let Ok(c) = self._chop_char() {
+            match c {
+                '\\' => {
+                    let _ = self._chop_char();
+                }
+                '\n' => break,
+                c if c == quote => break,
+                _ => {}
+            }
         }
+    }
 
-        if &self.content[self.cursor..(self.cursor + pref_len)] != prefix {
-            return false;
+    /**
+     * Consumes a template literal starting at the opening backtick, including any
+     * nested `${}` substitutions.
+     */
+    fn _chop_template(&mut self) {
+        self._chop_char().unwrap();
+
+        while let Ok(c) = self._chop_char() {
+            match c {
+                '\\' => {
+                    let _ = self._chop_char();
+                }
+                '`' => break,
+                '$' if self._peek_char(0) == Some('{') => {
+                    self._chop_char().unwrap();
+                    self._chop_substitution();
+                }
+                _ => {}
+            }
         }
+    }
+
+    /**
+     * Consumes the expression of a `${}` substitution up to its closing brace.
+     */
+    fn _chop_substitution(&mut self) {
+        let mut braces_count = 0;
 
-        return true;
+        while let Some(c) = self._peek_char(0) {
+            match c {
+                '\'' | '"' => self._chop_string(),
+                '`' => self._chop_template(),
+                '{' => {
+                    braces_count += 1;
+                    self._chop_char().unwrap();
+                }
+                '}' => {
+                    self._chop_char().unwrap();
+                    if braces_count == 0 {
+                        return;
+                    }
+                    braces_count -= 1;
+                }
+                _ => {
+                    self._chop_char().unwrap();
+                }
+            }
+        }
     }
 
-    pub fn next(&mut self) -> Option<Token> {
-        self._trim();
+    /**
+     * Consumes a numeric literal: decimals, exponents, `0x`/`0o`/`0b` prefixes,
+     * `_` separators and the bigint `n` suffix.
+     */
+    fn _chop_number(&mut self) {
+        let radix_prefixed = self._peek_char(0) == Some('0')
+            && matches!(self._peek_char(1), Some('x' | 'X' | 'o' | 'O' | 'b' | 'B'));
+        let mut seen_dot = false;
+        let mut prev = '\0';
+
+        while let Some(c) = self._peek_char(0) {
+            let is_number_part = c.is_ascii_alphanumeric()
+                || c == '_'
+                || (c == '.' && !seen_dot && !radix_prefixed)
+                || ((c == '+' || c == '-') && (prev == 'e' || prev == 'E') && !radix_prefixed);
+
+            if !is_number_part {
+                break;
+            }
+
+            seen_dot |= c == '.';
+            prev = c;
+            self._chop_char().unwrap();
+        }
+    }
 
-        let mut token = Token {
+    fn _lex(&mut self) -> Option<Token<'a>> {
+        let mut token = self._next_token()?;
+        token.span.end = self.cursor;
+
+        if !token.token_kind.is_comment() {
+            self.prev_token = Some(token.clone());
+        }
+
+        Some(token)
+    }
+
+    fn _token_at_cursor(&self) -> Token<'a> {
+        Token {
             text: &self.content[self.cursor..],
             text_len: 0,
             token_kind: TokenKind::TokenInvalid,
+            span: Span {
+                start: self.cursor,
+                end: self.cursor,
+                line: self.line + 1,
+                column: self.column + 1,
+            },
+        }
+    }
+
+    /**
+     * Lexes a `//` or `/* */` comment at the cursor into `token`. Returns false when
+     * the cursor is not at a comment.
+     */
+    fn _chop_comment(&mut self, token: &mut Token<'a>) -> bool {
+        if self._starts_with("//") {
+            token.token_kind = TokenKind::TokenComment;
+            self._chop_line();
+        } else if self._starts_with("/*") {
+            token.token_kind = if self._starts_with("/**") && !self._starts_with("/**/") {
+                TokenKind::TokenDocComment
+            } else {
+                TokenKind::TokenBlockComment
+            };
+            self._chop_str("/*");
+            while self.cursor < self.content_len && !self._starts_with("*/") {
+                self._chop_char().unwrap();
+            }
+            if self.cursor < self.content_len {
+                self._chop_str("*/");
+            }
+        } else {
+            return false;
+        }
+
+        token.text_len = token.text.len() - self.content[self.cursor..].len();
+        true
+    }
+
+    /**
+     * Checks that the `<` at the cursor opens a JSX element rather than a generic
+     * arrow function, which .tsx files write as `<T,>() => {}` or
+     * `<T extends unknown>() => {}`, or a generic function type such as
+     * `type Fn = <T>(x: T) => T`.
+     */
+    fn _at_jsx_element(&self) -> bool {
+        let rest = &self.content[self.cursor + 1..];
+
+        if rest.starts_with('>') {
+            return true;
+        }
+        if !rest.chars().next().is_some_and(is_symbol_start) || is_type_parameter_list(rest) {
+            return false;
+        }
+
+        let after_name = rest.trim_start_matches(|c: char| is_symbol(c) || c == '-' || c == '.');
+        let after_name = after_name.trim_start();
+
+        !(after_name.starts_with(',')
+            || after_name
+                .strip_prefix("extends")
+                .is_some_and(|after| !after.chars().next().is_some_and(is_symbol)))
+    }
+
+    /**
+     * Keeps track of the braces of a JSX `{}` expression, leaving the expression on
+     * its closing brace.
+     */
+    fn _track_jsx_braces(&mut self, token_kind: &TokenKind) {
+        let Some(JsxContext::Expression { braces_count }) = self.jsx_stack.last_mut() else {
+            return;
         };
 
-        if self.cursor >= self.content_len {
-            return None;
+        match token_kind {
+            TokenKind::TokenOpenCurly => *braces_count += 1,
+            TokenKind::TokenCloseCurly if *braces_count == 0 => {
+                self.jsx_stack.pop();
+            }
+            TokenKind::TokenCloseCurly => *braces_count -= 1,
+            _ => {}
+        }
+    }
+
+    /**
+     * Lexes the inside of a JSX tag: names (which may contain `-`), attribute values,
+     * which have no escape sequences, `{}` expressions and the closing `>` or `/>`.
+     */
+    fn _next_jsx_tag_token(&mut self) -> Option<Token<'a>> {
+        self._trim();
+
+        let mut token = self._token_at_cursor();
+        let start = self.cursor;
+
+        if self._chop_comment(&mut token) {
+            return Some(token);
         }
 
-        for i in 0..LITERAL_TOKENS.len() {
-            if self._starts_with(LITERAL_TOKENS[i].text) {
-                token.token_kind = LITERAL_TOKENS[i].token_kind.clone();
-                token.text_len += LITERAL_TOKENS[i].text.len();
-                self.cursor += LITERAL_TOKENS[i].text.len();
-                return Some(token);
+        match self._peek_char(0)? {
+            '>' => {
+                self._chop_char().unwrap();
+                token.token_kind = TokenKind::TokenGt;
+                match self.jsx_stack.pop() {
+                    Some(JsxContext::Tag(JsxTag::Opening)) => {
+                        self.jsx_stack.push(JsxContext::Children);
+                    }
+                    // leave the children of the element
+                    Some(JsxContext::Tag(JsxTag::Closing)) => {
+                        self.jsx_stack.pop();
+                    }
+                    _ => {}
+                }
+            }
+            '/' => {
+                self._chop_char().unwrap();
+                token.token_kind = TokenKind::TokenSlash;
+                if let Some(JsxContext::Tag(tag @ JsxTag::Opening)) = self.jsx_stack.last_mut() {
+                    *tag = JsxTag::SelfClosing;
+                }
+            }
+            '{' => {
+                self._chop_char().unwrap();
+                token.token_kind = TokenKind::TokenOpenCurly;
+                self.jsx_stack
+                    .push(JsxContext::Expression { braces_count: 0 });
+            }
+            quote @ ('\'' | '"') => {
+                self._chop_char().unwrap();
+                while let Ok(c) = self._chop_char() {
+                    if c == quote {
+                        break;
+                    }
+                }
+                token.token_kind = TokenKind::TokenString;
+            }
+            c if is_symbol_start(c) => {
+                while self._peek_char(0).is_some_and(|c| is_symbol(c) || c == '-') {
+                    self._chop_char().unwrap();
+                }
+                token.token_kind = TokenKind::TokenSymbol;
+            }
+            c => {
+                self._chop_char().unwrap();
+                if let Some(literal) = LITERAL_TOKENS
+                    .iter()
+                    .find(|literal| literal.text.len() == 1 && literal.text.starts_with(c))
+                {
+                    token.token_kind = literal.token_kind.clone();
+                }
             }
         }
 
-        if self._starts_with("//") {
-            token.token_kind = TokenKind::TokenComment;
-            while self.cursor < self.content_len && self._curr_cursor_char() != '\n' {
+        token.text_len = self.cursor - start;
+        Some(token)
+    }
+
+    /**
+     * Lexes the children of a JSX element: text up to the next `<` or `{`, child
+     * elements and `{}` expressions.
+     */
+    fn _next_jsx_child_token(&mut self) -> Option<Token<'a>> {
+        self._trim();
+
+        let mut token = self._token_at_cursor();
+        let start = self.cursor;
+
+        match self._peek_char(0)? {
+            '{' => {
                 self._chop_char().unwrap();
+                token.token_kind = TokenKind::TokenOpenCurly;
+                self.jsx_stack
+                    .push(JsxContext::Expression { braces_count: 0 });
             }
-            if self.cursor < self.content_len {
+            '<' => {
                 self._chop_char().unwrap();
+                token.token_kind = TokenKind::TokenLt;
+                let tag = if self.content[self.cursor..].trim_start().starts_with('/') {
+                    JsxTag::Closing
+                } else {
+                    JsxTag::Opening
+                };
+                self.jsx_stack.push(JsxContext::Tag(tag));
+            }
+            _ => {
+                while self._peek_char(0).is_some_and(|c| c != '<' && c != '{') {
+                    self._chop_char().unwrap();
+                }
+                token.token_kind = TokenKind::TokenJsxText;
             }
+        }
+
+        token.text_len = self.cursor - start;
+        Some(token)
+    }
+
+    fn _next_token(&mut self) -> Option<Token<'a>> {
+        match self.jsx_stack.last() {
+            Some(JsxContext::Tag(_)) => return self._next_jsx_tag_token(),
+            Some(JsxContext::Children) => return self._next_jsx_child_token(),
+            _ => {}
+        }
+
+        self._trim();
+
+        let mut token = self._token_at_cursor();
+
+        if self.cursor >= self.content_len {
+            return None;
+        }
+
+        // a hashbang is only valid as the very first line of a file
+        if self.cursor == 0 && self._starts_with("#!") {
+            token.token_kind = TokenKind::TokenHashbang;
+            self._chop_line();
             token.text_len = token.text.len() - self.content[self.cursor..].len();
             return Some(token);
         }
 
-        if self._curr_cursor_char() == '*' {
-            token.token_kind = TokenKind::TokenStar;
+        if self._chop_comment(&mut token) {
+            return Some(token);
+        }
+
+        if self._curr_cursor_char() == '/' && self._regex_allowed() {
+            token.token_kind = TokenKind::TokenRegex;
+            self._chop_regex();
+            token.text_len = token.text.len() - self.content[self.cursor..].len();
+            return Some(token);
+        }
+
+        if self.jsx
+            && self._curr_cursor_char() == '<'
+            && self._regex_allowed()
+            && self._at_jsx_element()
+        {
+            self._chop_char().unwrap();
+            token.token_kind = TokenKind::TokenLt;
             token.text_len = 1;
-            self.cursor += 1;
+            self.jsx_stack.push(JsxContext::Tag(JsxTag::Opening));
+            return Some(token);
+        }
+
+        let start = self.cursor;
+
+        match self._curr_cursor_char() {
+            '\'' | '"' => {
+                self._chop_string();
+                token.token_kind = TokenKind::TokenString;
+            }
+            '`' => {
+                self._chop_template();
+                token.token_kind = TokenKind::TokenTemplate;
+            }
+            c if is_number_start(c)
+                || (c == '.' && self._peek_char(1).is_some_and(is_number_start)) =>
+            {
+                self._chop_number();
+                token.token_kind = TokenKind::TokenNumber;
+            }
+            _ => {}
+        }
+
+        if token.token_kind != TokenKind::TokenInvalid {
+            token.text_len = self.cursor - start;
+            return Some(token);
+        }
+
+        let literal = LITERAL_TOKENS
+            .iter()
+            .filter(|literal| self._starts_with(literal.text))
+            // `a?.5:b` is a conditional, not an optional chain
+            .filter(|literal| {
+                literal.token_kind != TokenKind::TokenOptionalChain
+                    || !self._peek_char(2).is_some_and(is_number_start)
+            })
+            .max_by_key(|literal| literal.text.len());
+
+        if let Some(literal) = literal {
+            token.token_kind = literal.token_kind.clone();
+            token.text_len += literal.text.len();
+            self._chop_str(literal.text);
+            self._track_jsx_braces(&token.token_kind);
             return Some(token);
         }
 
         if is_symbol_start(self._curr_cursor_char()) {
             token.token_kind = TokenKind::TokenSymbol;
-            while self.cursor <= self.content_len && is_symbol(self._curr_cursor_char()) {
+            while self.cursor < self.content_len && is_symbol(self._curr_cursor_char()) {
                 self._chop_char().unwrap();
-                token.text_len += 1;
             }
-            for i in 0..TYPESCRIPT_KEYWORDS.len() {
-                if TYPESCRIPT_KEYWORDS[i] == token.get_text() {
-                    token.token_kind = TokenKind::TokenKeyword;
-                    break;
-                }
+            token.text_len = self.cursor - start;
+            if TYPESCRIPT_KEYWORDS.contains(&&token.text[..token.text_len]) {
+                token.token_kind = TokenKind::TokenKeyword;
             }
             return Some(token);
         }
 
-        self.cursor += 1;
-        token.text_len = 1;
+        self._chop_char().unwrap();
+        token.text_len = self.cursor - start;
         Some(token)
     }
 }
 
+impl<'a> Iterator for Lexer<'a> {
+    type Item = Token<'a>;
+
+    fn next(&mut self) -> Option<Token<'a>> {
+        self.lookahead.pop_front().or_else(|| self._lex())
+    }
+}
+
 #[test]
 fn test_lexer_import() {
     let expected: &[Token] = &[
@@ -178,97 +945,87 @@ fn test_lexer_import() {
             text: "import",
             text_len: 6,
             token_kind: TokenKind::TokenKeyword,
+            span: Span::default(),
         },
         Token {
             text: "{",
             text_len: 1,
             token_kind: TokenKind::TokenOpenCurly,
+            span: Span::default(),
         },
         Token {
             text: "myfunc",
             text_len: 6,
             token_kind: TokenKind::TokenSymbol,
+            span: Span::default(),
         },
         Token {
             text: ",",
             text_len: 1,
             token_kind: TokenKind::TokenComma,
+            span: Span::default(),
         },
         Token {
             text: "myvar",
             text_len: 5,
             token_kind: TokenKind::TokenSymbol,
+            span: Span::default(),
         },
         Token {
             text: "}",
             text_len: 1,
             token_kind: TokenKind::TokenCloseCurly,
+            span: Span::default(),
         },
         Token {
             text: "from",
             text_len: 4,
             token_kind: TokenKind::TokenKeyword,
+            span: Span::default(),
         },
         Token {
-            text: "'",
-            text_len: 1,
-            token_kind: TokenKind::TokenInvalid,
-        },
-        Token {
-            text: "asds",
-            text_len: 4,
-            token_kind: TokenKind::TokenSymbol,
-        },
-        Token {
-            text: "'",
-            text_len: 1,
-            token_kind: TokenKind::TokenInvalid,
+            text: "'asds'",
+            text_len: 6,
+            token_kind: TokenKind::TokenString,
+            span: Span::default(),
         },
         Token {
             text: ";",
             text_len: 1,
-            token_kind: TokenKind::TokenInvalid,
+            token_kind: TokenKind::TokenSemicolon,
+            span: Span::default(),
         },
         Token {
             text: "const",
             text_len: 5,
             token_kind: TokenKind::TokenKeyword,
+            span: Span::default(),
         },
         Token {
             text: "myv",
             text_len: 3,
             token_kind: TokenKind::TokenSymbol,
+            span: Span::default(),
         },
         Token {
             text: "=",
             text_len: 1,
-            token_kind: TokenKind::TokenInvalid,
-        },
-        Token {
-            text: "'",
-            text_len: 1,
-            token_kind: TokenKind::TokenInvalid,
+            token_kind: TokenKind::TokenEq,
+            span: Span::default(),
         },
         Token {
-            text: "sad",
-            text_len: 3,
-            token_kind: TokenKind::TokenSymbol,
-        },
-        Token {
-            text: "'",
-            text_len: 1,
-            token_kind: TokenKind::TokenInvalid,
+            text: "'sad'",
+            text_len: 5,
+            token_kind: TokenKind::TokenString,
+            span: Span::default(),
         },
     ];
-    let mut idx = 0;
-
     let content = "import{myfunc,myvar} from 'asds'; const myv = 'sad'";
-    let mut l = Lexer::new(content.clone(), content.len());
-    while let Some(tok) = l.next() {
+    let l = Lexer::new(content, content.len());
+    for (idx, tok) in l.enumerate() {
         assert_eq!(expected[idx].text, tok.get_text());
         assert_eq!(expected[idx].text_len, tok.text_len);
         assert_eq!(expected[idx].token_kind, tok.token_kind);
-        idx += 1;
     }
 }
 
@@ -279,36 +1036,379 @@ fn test_lexer_comment() {
             token_kind: TokenKind::TokenKeyword,
             text: "const",
             text_len: 5,
+            span: Span::default(),
         },
         Token {
             token_kind: TokenKind::TokenSymbol,
             text: "val",
             text_len: 3,
+            span: Span::default(),
         },
         Token {
-            token_kind: TokenKind::TokenInvalid,
+            token_kind: TokenKind::TokenEq,
             text: "=",
             text_len: 1,
+            span: Span::default(),
         },
         Token {
-            token_kind: TokenKind::TokenInvalid,
+            token_kind: TokenKind::TokenNumber,
             text: "4",
             text_len: 1,
+            span: Span::default(),
         },
         Token {
             token_kind: TokenKind::TokenComment,
             text: "// asdasdasd",
             text_len: 12,
+            span: Span::default(),
         },
     ];
-    let mut idx = 0;
-
     let content = "const val = 4 // asdasdasd";
-    let mut l = Lexer::new(content.clone(), content.len());
-    while let Some(tok) = l.next() {
+    let l = Lexer::new(content, content.len());
+    for (idx, tok) in l.enumerate() {
         assert_eq!(expected[idx].text, tok.get_text());
         assert_eq!(expected[idx].text_len, tok.text_len);
         assert_eq!(expected[idx].token_kind, tok.token_kind);
-        idx += 1;
     }
 }
+
+#[test]
+fn test_lexer_string_values() {
+    let content = r#"'./foo-bar.ts' "it\'s \"quoted\"" '\x41\u0042\u{43}' 'a\
+b'"#;
+    let l = Lexer::new(content, content.len());
+    let mut values = Vec::new();
+    for tok in l {
+        assert_eq!(tok.token_kind, TokenKind::TokenString);
+        values.push(tok.value().unwrap());
+    }
+    assert_eq!(values, vec!["./foo-bar.ts", "it's \"quoted\"", "ABC", "ab"]);
+}
+
+#[test]
+fn test_lexer_template() {
+    let content = "`./a/${`${b}`}/${ { c: '}' }.c }` `plain\\n` x";
+    let mut l = Lexer::new(content, content.len());
+
+    let tok = l.next().unwrap();
+    assert_eq!(tok.token_kind, TokenKind::TokenTemplate);
+    assert_eq!(tok.get_text(), "`./a/${`${b}`}/${ { c: '}' }.c }`");
+    assert_eq!(tok.value(), None);
+
+    let tok = l.next().unwrap();
+    assert_eq!(tok.token_kind, TokenKind::TokenTemplate);
+    assert_eq!(tok.value().unwrap(), "plain\n");
+
+    assert_eq!(l.next().unwrap().get_text(), "x");
+}
+
+#[test]
+fn test_lexer_numbers() {
+    let content = "42 3.14 .5 1e-7 0xFF 1_000n";
+    let l = Lexer::new(content, content.len());
+    let mut values = Vec::new();
+    for tok in l {
+        assert_eq!(tok.token_kind, TokenKind::TokenNumber);
+        values.push(tok.value().unwrap());
+    }
+    assert_eq!(values, vec!["42", "3.14", ".5", "1e-7", "0xFF", "1000n"]);
+}
+
+#[test]
+fn test_lexer_block_comments() {
+    let content = "#!/usr/bin/env node\n/* import './a' */ a /**/ /**\n * Adds.\n * @deprecated use sum\n */ b";
+    let l = Lexer::new(content, content.len());
+    let mut tokens = Vec::new();
+    for tok in l {
+        tokens.push((tok.token_kind.clone(), tok.get_text(), tok.doc_text()));
+    }
+    assert_eq!(
+        tokens,
+        vec![
+            (
+                TokenKind::TokenHashbang,
+                "#!/usr/bin/env node\n".to_string(),
+                None
+            ),
+            (
+                TokenKind::TokenBlockComment,
+                "/* import './a' */".to_string(),
+                None
+            ),
+            (TokenKind::TokenSymbol, "a".to_string(), None),
+            (TokenKind::TokenBlockComment, "/**/".to_string(), None),
+            (
+                TokenKind::TokenDocComment,
+                "/**\n * Adds.\n * @deprecated use sum\n */".to_string(),
+                Some("Adds.\n@deprecated use sum".to_string())
+            ),
+            (TokenKind::TokenSymbol, "b".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_lexer_regex() {
+    let content =
+        "const re = /import '.\\/x'/g; x = a / b / c; if (/[/]\\/\\//.test(s)) return /a/i; await /'/.test(s)";
+    let l = Lexer::new(content, content.len());
+    let mut tokens = Vec::new();
+    for tok in l {
+        if tok.token_kind == TokenKind::TokenRegex || tok.get_text() == "/" {
+            tokens.push((tok.token_kind.clone(), tok.get_text()));
+        }
+    }
+    assert_eq!(
+        tokens,
+        vec![
+            (TokenKind::TokenRegex, "/import '.\\/x'/g".to_string()),
+            (TokenKind::TokenSlash, "/".to_string()),
+            (TokenKind::TokenSlash, "/".to_string()),
+            (TokenKind::TokenRegex, "/[/]\\/\\//".to_string()),
+            (TokenKind::TokenRegex, "/a/i".to_string()),
+            (TokenKind::TokenRegex, "/'/".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_lexer_punctuators() {
+    let content = "export * as ns from; a?.b ?? c; x?.5:y; f = (...args) => a >>>= b !== c; <T>";
+    let l = Lexer::new(content, content.len());
+    let mut kinds = Vec::new();
+    for tok in l {
+        kinds.push(tok.token_kind);
+    }
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::TokenKeyword,
+            TokenKind::TokenStar,
+            TokenKind::TokenKeyword,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenKeyword,
+            TokenKind::TokenSemicolon,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenOptionalChain,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenQuestionQuestion,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenSemicolon,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenQuestion,
+            TokenKind::TokenNumber,
+            TokenKind::TokenColon,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenSemicolon,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenEq,
+            TokenKind::TokenOpenParen,
+            TokenKind::TokenEllipsis,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenCloseParen,
+            TokenKind::TokenArrow,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenUShrEq,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenNotEqEq,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenSemicolon,
+            TokenKind::TokenLt,
+            TokenKind::TokenSymbol,
+            TokenKind::TokenGt,
+        ]
+    );
+}
+
+#[test]
+fn test_lexer_spans() {
+    let content = "import a\n  from './a';";
+    let l = Lexer::new(content, content.len());
+    let mut spans = Vec::new();
+    for tok in l {
+        spans.push(tok.span);
+    }
+    assert_eq!(
+        spans,
+        vec![
+            Span {
+                start: 0,
+                end: 6,
+                line: 1,
+                column: 1
+            },
+            Span {
+                start: 7,
+                end: 8,
+                line: 1,
+                column: 8
+            },
+            Span {
+                start: 11,
+                end: 15,
+                line: 2,
+                column: 3
+            },
+            Span {
+                start: 16,
+                end: 21,
+                line: 2,
+                column: 8
+            },
+            Span {
+                start: 21,
+                end: 22,
+                line: 2,
+                column: 13
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_lexer_utf8() {
+    let content = "const 名前 = '😀 import x from \"y\"'; // ✓\n  émoji × 2";
+    let l = Lexer::new(content, content.len());
+    let mut tokens = Vec::new();
+    for tok in l {
+        tokens.push((tok.token_kind.clone(), tok.get_text(), tok.span.to_string()));
+    }
+    assert_eq!(
+        tokens,
+        vec![
+            (
+                TokenKind::TokenKeyword,
+                "const".to_string(),
+                "1:1".to_string()
+            ),
+            (
+                TokenKind::TokenSymbol,
+                "名前".to_string(),
+                "1:7".to_string()
+            ),
+            (TokenKind::TokenEq, "=".to_string(), "1:10".to_string()),
+            (
+                TokenKind::TokenString,
+                "'😀 import x from \"y\"'".to_string(),
+                "1:12".to_string()
+            ),
+            (
+                TokenKind::TokenSemicolon,
+                ";".to_string(),
+                "1:33".to_string()
+            ),
+            (
+                TokenKind::TokenComment,
+                "// ✓\n".to_string(),
+                "1:35".to_string()
+            ),
+            (
+                TokenKind::TokenSymbol,
+                "émoji".to_string(),
+                "2:3".to_string()
+            ),
+            (TokenKind::TokenInvalid, "×".to_string(), "2:9".to_string()),
+            (TokenKind::TokenNumber, "2".to_string(), "2:11".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_lexer_large_input() {
+    let content = "import { a } from './a'; // ünïcödé\n".repeat(5_000);
+    let mut l = Lexer::new(&content, content.len());
+    let mut count = 0;
+    while l.next().is_some() {
+        count += 1;
+    }
+    assert_eq!(count, 8 * 5_000);
+}
+
+#[test]
+fn test_lexer_lookahead() {
+    let content = "import type X = require('y');";
+    let mut l = Lexer::new(content, content.len());
+
+    assert_eq!(l.peek().unwrap().get_text(), "import");
+    assert_eq!(l.peek_nth(3).unwrap().token_kind, TokenKind::TokenEq);
+    assert_eq!(l.next().unwrap().get_text(), "import");
+
+    let checkpoint = l.checkpoint();
+    let skipped: Vec<String> = l
+        .by_ref()
+        .take_while(|tok| tok.token_kind != TokenKind::TokenOpenParen)
+        .map(|tok| tok.get_text())
+        .collect();
+    assert_eq!(skipped, vec!["type", "X", "=", "require"]);
+    assert_eq!(l.next().unwrap().value().unwrap(), "y");
+
+    l.rewind(checkpoint);
+    assert_eq!(l.next().unwrap().get_text(), "type");
+    assert_eq!(
+        l.filter(|tok| tok.token_kind == TokenKind::TokenString)
+            .count(),
+        1
+    );
+}
+
+#[test]
+fn test_lexer_jsx() {
+    let content = r#"const el = <p className="it's">Don't {/* c */} import {list.map(i => <Item key={i} />)}</p>;"#;
+    let l = Lexer::new(content, content.len()).with_jsx(true);
+    let tokens: Vec<(TokenKind, String)> = l
+        .map(|tok| (tok.token_kind.clone(), tok.get_text()))
+        .collect();
+    let expected = vec![
+        (TokenKind::TokenKeyword, "const"),
+        (TokenKind::TokenSymbol, "el"),
+        (TokenKind::TokenEq, "="),
+        (TokenKind::TokenLt, "<"),
+        (TokenKind::TokenSymbol, "p"),
+        (TokenKind::TokenSymbol, "className"),
+        (TokenKind::TokenEq, "="),
+        (TokenKind::TokenString, "\"it's\""),
+        (TokenKind::TokenGt, ">"),
+        (TokenKind::TokenJsxText, "Don't "),
+        (TokenKind::TokenOpenCurly, "{"),
+        (TokenKind::TokenBlockComment, "/* c */"),
+        (TokenKind::TokenCloseCurly, "}"),
+        (TokenKind::TokenJsxText, "import "),
+        (TokenKind::TokenOpenCurly, "{"),
+        (TokenKind::TokenSymbol, "list"),
+        (TokenKind::TokenDot, "."),
+        (TokenKind::TokenSymbol, "map"),
+        (TokenKind::TokenOpenParen, "("),
+        (TokenKind::TokenSymbol, "i"),
+        (TokenKind::TokenArrow, "=>"),
+        (TokenKind::TokenLt, "<"),
+        (TokenKind::TokenSymbol, "Item"),
+        (TokenKind::TokenSymbol, "key"),
+        (TokenKind::TokenEq, "="),
+        (TokenKind::TokenOpenCurly, "{"),
+        (TokenKind::TokenSymbol, "i"),
+        (TokenKind::TokenCloseCurly, "}"),
+        (TokenKind::TokenSlash, "/"),
+        (TokenKind::TokenGt, ">"),
+        (TokenKind::TokenCloseParen, ")"),
+        (TokenKind::TokenCloseCurly, "}"),
+        (TokenKind::TokenLt, "<"),
+        (TokenKind::TokenSlash, "/"),
+        (TokenKind::TokenSymbol, "p"),
+        (TokenKind::TokenGt, ">"),
+        (TokenKind::TokenSemicolon, ";"),
+    ];
+    let expected: Vec<(TokenKind, String)> = expected
+        .into_iter()
+        .map(|(kind, text)| (kind, text.to_string()))
+        .collect();
+    assert_eq!(tokens, expected);
+}
+
+#[test]
+fn test_lexer_jsx_generic_arrow() {
+    let content = "const id = <T,>(x: T) => x < y;";
+    let l = Lexer::new(content, content.len()).with_jsx(true);
+    assert!(l
+        .map(|tok| tok.token_kind)
+        .all(|kind| kind != TokenKind::TokenJsxText));
+}
@@ -1,4 +1,3 @@
-use path_absolutize::*;
 use petgraph::dot::Config;
 use petgraph::dot::Dot;
 use petgraph::stable_graph::NodeIndex;
@@ -8,28 +7,79 @@ use std::collections::VecDeque;
 use std::fs::File;
 use std::fs::{self};
 use std::io::Write;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 mod cli;
+mod dependencies;
+mod exports;
+#[cfg(test)]
+mod fixture;
+mod json;
+mod lexer;
+mod packages;
 mod parser;
+mod resolver;
 mod structs;
-use cli::parse_args;
+mod tsconfig;
+mod workspace;
+use cli::{parse_args, CliOptions};
+use dependencies::{check_dependencies, imported_package, is_production_file, PackageImport};
+use exports::find_unused_exports;
+use packages::{
+    external_module, find_package_json, split_package_specifier, types_package, PackageJson,
+};
 use parser::find_imported_files;
-use structs::TsFile;
+use resolver::{is_source_file, Resolver};
+use structs::{Dependency, ExternalKind, TsFile, TsImport, TsImportKind, TsImportSource};
+use tsconfig::{find_tsconfig, load_tsconfig};
+use workspace::{find_workspace_packages, package_of};
 
 fn main() {
-    if let Ok(Some(source_code_dir)) = parse_args() {
-        generate_project_graph(source_code_dir.as_str());
+    if let Ok(Some(options)) = parse_args() {
+        let unresolved = generate_project_graph(&options);
+
+        if options.strict && unresolved > 0 {
+            eprintln!("ERROR: {} unresolved imports", unresolved);
+            std::process::exit(1);
+        }
     }
 }
 
-fn generate_project_graph(src_dir: &str) {
-    let mut graph: Graph<String, String> = Graph::new();
+/**
+ * Writes the graph of the project and the reports asked for in `options`. Returns
+ * the number of relative imports that do not resolve to a file.
+ */
+fn generate_project_graph(options: &CliOptions) -> usize {
+    let src_dir = options.directory.as_str();
+    let mut graph: Graph<String, Dependency> = Graph::new();
     let mut path_to_ts_file: HashMap<String, NodeIndex> = HashMap::new();
+    let mut symbol_graph: Graph<String, Dependency> = Graph::new();
+    let mut symbol_to_node: HashMap<String, NodeIndex> = HashMap::new();
+    let mut external_nodes: HashMap<NodeIndex, ExternalKind> = HashMap::new();
 
     let dir = Path::new(src_dir);
 
-    let files =
+    let mut files =
         visit_dirs(dir).unwrap_or_else(|| panic!("ERROR reading directory: {}", dir.display()));
+    let workspace_packages = if options.workspace {
+        let packages = find_workspace_packages(dir);
+        if packages.is_empty() {
+            eprintln!("WARNING: no workspace packages found in {}", dir.display());
+        }
+        packages
+    } else {
+        Vec::new()
+    };
+    let resolver = load_resolver(options, dir).with_workspace_packages(workspace_packages.clone());
+    resolver.resolve_imports(&mut files);
+
+    let mut package_graph: Graph<String, Dependency> = Graph::new();
+    let package_to_node: HashMap<&Path, NodeIndex> = workspace_packages
+        .iter()
+        .filter_map(|package| {
+            let node = package_graph.add_node(package.name()?.to_string());
+            Some((package.dir.as_path(), node))
+        })
+        .collect();
 
     // create a hashmap of the file path to a graph node
     for file in &files {
@@ -41,73 +91,539 @@ fn generate_project_graph(src_dir: &str) {
     }
 
     for visiting_file in &files {
-        let visting_file_relative_path = Path::new(visiting_file.relative_path.as_str());
         let visiting_file_node_key =
             get_base_project_path(dir, Path::new(&visiting_file.relative_path.clone()));
 
         // for each file visit its dependancies (imports) and populate the graph
         for import in &visiting_file.imports {
-            let import_path = Path::new(import.source.as_str());
-            let import_abs_path = abs_path_from_dir_to_file(
-                visting_file_relative_path.parent().unwrap(),
-                import_path,
-            );
+            if let Some(import_type) = &options.import_type {
+                if import.attribute_type() != Some(import_type.as_str()) {
+                    continue;
+                }
+            }
+
+            let Some(import_abs_path) = import.resolved.clone() else {
+                if options.external {
+                    if let Some(&from) = path_to_ts_file.get(&visiting_file_node_key) {
+                        add_external_edge(
+                            &mut graph,
+                            &mut path_to_ts_file,
+                            &mut external_nodes,
+                            from,
+                            visiting_file,
+                            import,
+                            &resolver,
+                        );
+                    }
+                }
+                continue;
+            };
+
+            let from_package =
+                package_of(&workspace_packages, Path::new(&visiting_file.relative_path));
+            let to_package = package_of(&workspace_packages, &import_abs_path);
+            if let (Some(from_package), Some(to_package)) = (from_package, to_package) {
+                if from_package.dir != to_package.dir {
+                    add_dependency_edge(
+                        &mut package_graph,
+                        package_to_node[from_package.dir.as_path()],
+                        package_to_node[to_package.dir.as_path()],
+                        Dependency {
+                            kind: import.kind,
+                            attribute_type: None,
+                        },
+                    );
+                }
+            }
             let import_base_path =
                 get_base_project_path(dir, Path::new(&import_abs_path.to_str().unwrap()));
 
+            // files that are not parsed, such as JSON and CSS modules, get a node
+            // once imported
+            if !path_to_ts_file.contains_key(&import_base_path) {
+                let g_node = graph.add_node(import_base_path.clone());
+                path_to_ts_file.insert(import_base_path.clone(), g_node);
+            }
+
             // get currently visiting file node
             if let Some(import_node) = path_to_ts_file.get(&visiting_file_node_key) {
                 // get currently visiting files import node
                 if let Some(visiting_file_dependancy_node) =
                     path_to_ts_file.get(import_base_path.as_str())
                 {
-                    graph.update_edge(
+                    if options.symbols {
+                        add_symbol_edges(
+                            &mut symbol_graph,
+                            &mut symbol_to_node,
+                            &visiting_file_node_key,
+                            &import_base_path,
+                            import,
+                        );
+                    }
+
+                    add_dependency_edge(
+                        &mut graph,
                         *import_node,
                         *visiting_file_dependancy_node,
-                        String::from(import_abs_path.to_str().unwrap()),
+                        Dependency {
+                            kind: import.kind,
+                            attribute_type: import.attribute_type().map(String::from),
+                        },
                     );
                 }
             }
         }
     }
 
-    let cfg = Dot::with_config(&graph, &[Config::EdgeNoLabel]);
-    let mut f = File::create("example1.dot").unwrap();
-    let output = format!("{}", cfg);
+    for file in &files {
+        for import in &file.unresolvable_imports {
+            eprintln!(
+                "WARNING: unresolvable import {} at {}:{}",
+                import.expression, file.relative_path, import.span
+            );
+        }
+    }
+
+    let unresolved = find_unresolved_imports(dir, &files, &resolver);
+    if options.unresolved {
+        print_unresolved_imports(&unresolved);
+    }
+
+    if options.list_commonjs {
+        print_commonjs_files(dir, &files);
+    }
+
+    if options.unused_exports {
+        print_unused_exports(dir, &files, options.skip_public);
+    }
+
+    if options.check_dependencies {
+        print_dependency_check(dir, &files);
+    }
 
-    if f.write(output.as_bytes()).is_ok() {
+    let output_graph = if options.symbols {
+        &symbol_graph
+    } else {
+        &graph
+    };
+    let output_external_nodes = if options.symbols {
+        HashMap::new()
+    } else {
+        external_nodes
+    };
+    if write_graph(output_graph, &output_external_nodes, "example1.dot") {
         println!("Wrote output graph");
     } else {
         eprintln!("Error writing graph")
     }
+
+    if options.workspace {
+        if write_graph(&package_graph, &HashMap::new(), "packages.dot") {
+            println!("Wrote package graph");
+        } else {
+            eprintln!("Error writing package graph")
+        }
+    }
+
+    unresolved.len()
 }
 
-fn get_base_project_path(full_path: &Path, relative_path: &Path) -> String {
-    let abs_path = std::fs::canonicalize(full_path).unwrap();
-    let abs_path_str = abs_path.as_os_str().to_str().unwrap();
+/**
+ * Writes `graph` in the DOT format to `path`, with the edge styles of its
+ * dependency kinds and the node styles of its `external_nodes`. Returns false when
+ * the file cannot be written.
+ */
+fn write_graph(
+    graph: &Graph<String, Dependency>,
+    external_nodes: &HashMap<NodeIndex, ExternalKind>,
+    path: &str,
+) -> bool {
+    let node_attributes = |_, (node, _)| match external_nodes.get(&node) {
+        Some(ExternalKind::Package) => String::from("shape = box"),
+        Some(ExternalKind::Builtin) => String::from("shape = box, style = dashed"),
+        None => String::new(),
+    };
+    let cfg = Dot::with_attr_getters(
+        graph,
+        &[Config::EdgeNoLabel],
+        &|_, edge| edge_attributes(edge.weight()),
+        &node_attributes,
+    );
+    let output = format!("{}", cfg);
 
-    relative_path.to_str().unwrap().replace(abs_path_str, "")
+    File::create(path).is_ok_and(|mut f| f.write(output.as_bytes()).is_ok())
+}
+
+/**
+ * Creates a resolver with the aliases of the tsconfig given with `-p`, or else of
+ * the closest `tsconfig.json` to `dir`, and the `--conditions` to match in
+ * `package.json` maps.
+ */
+fn load_resolver(options: &CliOptions, dir: &Path) -> Resolver {
+    let tsconfig_path = match &options.tsconfig {
+        Some(tsconfig) => Some(PathBuf::from(tsconfig)),
+        None => find_tsconfig(dir),
+    };
+
+    let tsconfig = tsconfig_path
+        .and_then(|tsconfig_path| {
+            let tsconfig = load_tsconfig(&tsconfig_path);
+            if tsconfig.is_none() {
+                eprintln!("WARNING: unable to read {}", tsconfig_path.display());
+            }
+            tsconfig
+        })
+        .unwrap_or_default();
+
+    let resolver = Resolver::new(tsconfig);
+    match &options.conditions {
+        Some(conditions) => resolver.with_conditions(conditions.clone()),
+        None => resolver,
+    }
+}
+
+/**
+ * A relative import that no file on disk matches, whose edge is left out of the
+ * graph.
+ */
+struct UnresolvedImport {
+    // the importing file and the line and column of the import
+    location: String,
+    specifier: String,
+    // the paths tried, in order
+    candidates: Vec<String>,
+}
+
+/**
+ * Returns the relative imports of `files` that do not resolve, with the paths the
+ * resolver tried for each.
+ */
+fn find_unresolved_imports(
+    dir: &Path,
+    files: &[TsFile],
+    resolver: &Resolver,
+) -> Vec<UnresolvedImport> {
+    let mut unresolved = Vec::new();
+
+    for file in files {
+        let file_key = get_base_project_path(dir, Path::new(&file.relative_path));
+
+        for import in &file.imports {
+            if !matches!(import.import_source, TsImportSource::Local) || import.resolved.is_some() {
+                continue;
+            }
+
+            unresolved.push(UnresolvedImport {
+                location: format!("{}:{}", file_key, import.span),
+                specifier: import.specifier.clone(),
+                candidates: resolver
+                    .import_candidates(file, import)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|candidate| get_base_project_path(dir, candidate))
+                    .collect(),
+            });
+        }
+    }
+
+    unresolved
+}
+
+fn print_unresolved_imports(unresolved: &[UnresolvedImport]) {
+    println!("Unresolved imports ({}):", unresolved.len());
+    for import in unresolved {
+        println!("    {} {}", import.location, import.specifier);
+        for candidate in &import.candidates {
+            println!("        tried {}", candidate);
+        }
+    }
+}
+
+/**
+ * Prints the exports that are never imported within `dir`, to find dead code.
+ * With `skip_public`, exports tagged `@public` are left out.
+ */
+fn print_unused_exports(dir: &Path, files: &[TsFile], skip_public: bool) {
+    let mut unused: Vec<(String, String)> = find_unused_exports(
+        files,
+        |file| get_base_project_path(dir, Path::new(&file.relative_path)),
+        |_, import| {
+            import
+                .resolved
+                .as_ref()
+                .map(|path| get_base_project_path(dir, path))
+                .unwrap_or_default()
+        },
+    )
+    .into_iter()
+    .filter(|(_, export)| !(skip_public && export.is_public_api()))
+    .map(|(file, export)| {
+        (
+            format!(
+                "{}:{}",
+                get_base_project_path(dir, Path::new(&file.relative_path)),
+                export.span
+            ),
+            format!("{} ({})", export.name, export.kind),
+        )
+    })
+    .collect();
+    unused.sort();
+
+    println!("Unused exports ({}):", unused.len());
+    for (location, export) in unused {
+        println!("    {} {}", location, export);
+    }
+}
+
+/**
+ * Prints the packages that files import without their closest `package.json`
+ * declaring them, the declared dependencies that no file imports, and the
+ * `devDependencies` imported by production files.
+ */
+fn print_dependency_check(dir: &Path, files: &[TsFile]) {
+    let mut packages: Vec<(PackageJson, Vec<PackageImport>)> = Vec::new();
+
+    for file in files {
+        let path = Path::new(&file.relative_path);
+        let Some(package) = path.parent().and_then(find_package_json) else {
+            continue;
+        };
+        let index = match packages
+            .iter()
+            .position(|(other, _)| other.dir == package.dir)
+        {
+            Some(index) => index,
+            None => {
+                packages.push((package, Vec::new()));
+                packages.len() - 1
+            }
+        };
+
+        let production_file = is_production_file(&packages[index].0.dir, path);
+        for import in &file.imports {
+            if let Some(name) = imported_package(file, import) {
+                packages[index].1.push(PackageImport {
+                    name,
+                    file: get_base_project_path(dir, path),
+                    production: production_file && !import.is_type_only(),
+                    type_only: import.is_type_only(),
+                });
+            }
+        }
+    }
+    packages.sort_by(|(a, _), (b, _)| a.dir.cmp(&b.dir));
+
+    let mut undeclared = Vec::new();
+    let mut unused = Vec::new();
+    let mut dev_only = Vec::new();
+    for (package, imports) in &packages {
+        let package_json = get_base_project_path(dir, &package.dir.join("package.json"));
+        let check = check_dependencies(package, imports);
+
+        for (name, files) in check.undeclared {
+            undeclared.push(format!(
+                "{} {} imported by {}",
+                package_json,
+                name,
+                files.join(", ")
+            ));
+        }
+        for name in check.unused {
+            unused.push(format!("{} {}", package_json, name));
+        }
+        for (name, files) in check.dev_only {
+            dev_only.push(format!(
+                "{} {} imported by {}",
+                package_json,
+                name,
+                files.join(", ")
+            ));
+        }
+    }
+
+    for (title, lines) in [
+        ("Undeclared dependencies", undeclared),
+        ("Unused dependencies", unused),
+        ("Dev dependencies imported by production files", dev_only),
+    ] {
+        println!("{} ({}):", title, lines.len());
+        for line in lines {
+            println!("    {}", line);
+        }
+    }
 }
 
 /**
- * Takes in two paths original_path and relative_path and returns the absolute path of
- * relative_path.
- * Ex:
- * original_path: /Users/linus/src/utils/test
- * relative_path: ../util
- *
- * Returns: /Users/linus/src/utils
- *
- * Since the file util was imported relatively from /Users/linus/src/utils
+ * Adds an edge from `from` to `to` unless an equal one already exists, so a file
+ * that both imports and re-exports another file gets one edge of each kind.
  */
-fn abs_path_from_dir_to_file(original_path: &Path, relative_path: &Path) -> PathBuf {
-    let path = Path::new(original_path);
-    let parent = path;
+fn add_dependency_edge(
+    graph: &mut Graph<String, Dependency>,
+    from: NodeIndex,
+    to: NodeIndex,
+    dependency: Dependency,
+) {
+    if !graph
+        .edges_connecting(from, to)
+        .any(|edge| *edge.weight() == dependency)
+    {
+        graph.add_edge(from, to, dependency);
+    }
+}
 
-    let joined_path = parent.join(relative_path);
-    let p = Path::new(joined_path.as_path());
+/**
+ * Adds an edge from the file node `from` to the node of the installed package or
+ * Node.js builtin that `import` names, such as `lodash` for `lodash/fp`, or of the
+ * package a `/// <reference types="..." />` directive names. Imports of project
+ * files that do not resolve are left out.
+ */
+fn add_external_edge(
+    graph: &mut Graph<String, Dependency>,
+    path_to_ts_file: &mut HashMap<String, NodeIndex>,
+    external_nodes: &mut HashMap<NodeIndex, ExternalKind>,
+    from: NodeIndex,
+    file: &TsFile,
+    import: &TsImport,
+    resolver: &Resolver,
+) {
+    let is_package =
+        matches!(import.import_source, TsImportSource::Package) && !import.source.starts_with('#');
+    if !is_package {
+        return;
+    }
 
-    return PathBuf::from(p.absolutize().unwrap().to_str().unwrap());
+    let (name, kind) = match import.kind {
+        TsImportKind::TypeReference => (
+            type_reference_package(file, import, resolver),
+            ExternalKind::Package,
+        ),
+        _ => external_module(&import.source),
+    };
+    let to = *path_to_ts_file.entry(name.clone()).or_insert_with(|| {
+        let node = graph.add_node(name);
+        external_nodes.insert(node, kind);
+        node
+    });
+
+    add_dependency_edge(
+        graph,
+        from,
+        to,
+        Dependency {
+            kind: import.kind,
+            attribute_type: import.attribute_type().map(String::from),
+        },
+    );
+}
+
+/**
+ * Returns the package `/// <reference types="name" />` takes its declarations
+ * from: `@types/name` when they are installed there, or else `name` itself.
+ */
+fn type_reference_package(file: &TsFile, import: &TsImport, resolver: &Resolver) -> String {
+    let in_types = resolver
+        .import_candidates(file, import)
+        .unwrap_or_default()
+        .into_iter()
+        .find(|candidate| candidate.is_file())
+        .is_some_and(|path| {
+            path.components()
+                .any(|component| component == Component::Normal("@types".as_ref()))
+        });
+    let (name, _) = split_package_specifier(&import.source);
+
+    if in_types {
+        types_package(name)
+    } else {
+        name.to_string()
+    }
+}
+
+/**
+ * Adds the edges of the per-symbol graph, whose nodes are `file#exportName`, for
+ * the names `import` takes from the file `to_file`. Imports start from the
+ * importing file and re-exports from the symbol they export, so a chain of
+ * re-exports leads to the file declaring the symbol.
+ */
+fn add_symbol_edges(
+    graph: &mut Graph<String, Dependency>,
+    symbol_to_node: &mut HashMap<String, NodeIndex>,
+    from_file: &str,
+    to_file: &str,
+    import: &TsImport,
+) {
+    let dependency = Dependency {
+        kind: import.kind,
+        attribute_type: None,
+    };
+
+    for (imported, local) in import.imported_names() {
+        let from = if import.kind == TsImportKind::ReExport {
+            format!("{}#{}", from_file, local)
+        } else {
+            from_file.to_string()
+        };
+        let from = symbol_node(graph, symbol_to_node, from);
+        let to = symbol_node(graph, symbol_to_node, format!("{}#{}", to_file, imported));
+
+        add_dependency_edge(graph, from, to, dependency.clone());
+    }
+}
+
+fn symbol_node(
+    graph: &mut Graph<String, Dependency>,
+    symbol_to_node: &mut HashMap<String, NodeIndex>,
+    symbol: String,
+) -> NodeIndex {
+    *symbol_to_node
+        .entry(symbol.clone())
+        .or_insert_with(|| graph.add_node(symbol))
+}
+
+fn edge_attributes(dependency: &Dependency) -> String {
+    let style = edge_style(&dependency.kind);
+
+    match &dependency.attribute_type {
+        Some(attribute_type) if style.is_empty() => format!("label = \"{}\"", attribute_type),
+        Some(attribute_type) => format!("{}, label = \"{}\"", style, attribute_type),
+        None => style,
+    }
+}
+
+fn edge_style(kind: &TsImportKind) -> String {
+    match kind {
+        TsImportKind::Static => String::new(),
+        TsImportKind::ReExport => String::from("style = dashed"),
+        TsImportKind::Dynamic => String::from("style = dotted"),
+        TsImportKind::Require => String::from("style = bold"),
+        TsImportKind::TypeReference => String::from("color = gray"),
+    }
+}
+
+/**
+ * Prints the files that `require()` modules or assign to `module.exports`, which are
+ * left to migrate in a mixed ES module and CommonJS codebase.
+ */
+fn print_commonjs_files(dir: &Path, files: &[TsFile]) {
+    let mut commonjs_files: Vec<String> = files
+        .iter()
+        .filter(|file| file.uses_commonjs())
+        .map(|file| get_base_project_path(dir, Path::new(&file.relative_path)))
+        .collect();
+    commonjs_files.sort();
+
+    println!("Files using CommonJS ({}):", commonjs_files.len());
+    for file in commonjs_files {
+        println!("    {}", file);
+    }
+}
+
+fn get_base_project_path(full_path: &Path, relative_path: &Path) -> String {
+    let abs_path = std::fs::canonicalize(full_path).unwrap();
+    let abs_path_str = abs_path.as_os_str().to_str().unwrap();
+
+    relative_path.to_str().unwrap().replace(abs_path_str, "")
 }
 
 fn visit_dirs(dir: &Path) -> Option<Vec<TsFile>> {
@@ -122,25 +638,15 @@ fn visit_dirs(dir: &Path) -> Option<Vec<TsFile>> {
             let path = entry.path();
 
             if file_type.is_file() {
-                //
-                let ext = path.extension().unwrap_or_else(|| {
-                    panic!(
-                        "ERROR reading directory: {}",
-                        path.as_os_str().to_str().unwrap()
-                    )
-                });
-
-                // we only support ts files
-                if ext == "ts" {
-                    let ts_file = find_imported_files(&entry.path()).unwrap_or_else(|| {
-                        panic!(
-                            "ERROR reading filepath: {}",
-                            path.as_os_str().to_str().unwrap()
-                        )
-                    });
-                    ts_files.push(ts_file);
+                if is_source_file(&path) {
+                    match find_imported_files(&path) {
+                        Some(ts_file) => ts_files.push(ts_file),
+                        // e.g. a legacy JavaScript file that is not UTF-8
+                        None => eprintln!("WARNING: unable to read {}", path.display()),
+                    }
                 }
-            } else if file_type.is_dir() {
+            } else if file_type.is_dir() && !path.ends_with("node_modules") {
+                // installed packages are not part of the project
                 dir_queue.push_back(path);
             }
         }
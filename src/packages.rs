@@ -0,0 +1,373 @@
+use crate::json::{parse_json, JsonValue};
+use crate::structs::ExternalKind;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/**
+ * Conditions always matched in `exports` and `imports` maps, which `--conditions`
+ * adds to. `import` and `require` only match imports of their own module system,
+ * and `default` always matches.
+ */
+pub static DEFAULT_CONDITIONS: &[&str] = &["types", "import", "require", "node"];
+
+#[derive(Debug, Clone)]
+pub struct PackageJson {
+    // directory of the `package.json`
+    pub dir: PathBuf,
+    pub json: JsonValue,
+}
+
+/**
+ * Reads the `package.json` in `dir`, if any.
+ */
+pub fn read_package_json(dir: &Path) -> Option<PackageJson> {
+    let json = parse_json(&fs::read_to_string(dir.join("package.json")).ok()?)?;
+
+    Some(PackageJson {
+        dir: dir.to_path_buf(),
+        json,
+    })
+}
+
+/**
+ * Returns the `package.json` in `dir` or in its closest parent directory.
+ */
+pub fn find_package_json(dir: &Path) -> Option<PackageJson> {
+    dir.ancestors().find_map(read_package_json)
+}
+
+/**
+ * Splits a bare specifier into its package name and the subpath within the
+ * package, `.` for the package itself:
+ *
+ * lodash/fp => lodash, ./fp
+ * @scope/pkg/sub => @scope/pkg, ./sub
+ */
+pub fn split_package_specifier(specifier: &str) -> (&str, String) {
+    let name_len = if specifier.starts_with('@') {
+        specifier
+            .match_indices('/')
+            .nth(1)
+            .map_or(specifier.len(), |(index, _)| index)
+    } else {
+        specifier.find('/').unwrap_or(specifier.len())
+    };
+
+    let (name, subpath) = specifier.split_at(name_len);
+    (name, format!(".{}", subpath))
+}
+
+/**
+ * Modules built into Node.js, which may also be imported with the `node:` prefix.
+ */
+static NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "async_hooks",
+    "buffer",
+    "child_process",
+    "cluster",
+    "console",
+    "constants",
+    "crypto",
+    "dgram",
+    "diagnostics_channel",
+    "dns",
+    "domain",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "inspector",
+    "module",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "punycode",
+    "querystring",
+    "readline",
+    "repl",
+    "stream",
+    "string_decoder",
+    "sys",
+    "timers",
+    "tls",
+    "trace_events",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "wasi",
+    "worker_threads",
+    "zlib",
+];
+
+/**
+ * Returns the module outside the project a bare specifier refers to, without its
+ * subpath: the package name, or `node:name` for a Node.js builtin.
+ *
+ * lodash/fp => lodash
+ * @scope/pkg/sub => @scope/pkg
+ * fs/promises, node:fs => node:fs
+ */
+pub fn external_module(specifier: &str) -> (String, ExternalKind) {
+    if let Some(builtin) = specifier.strip_prefix("node:") {
+        let (name, _) = split_package_specifier(builtin);
+        return (format!("node:{}", name), ExternalKind::Builtin);
+    }
+
+    let (name, _) = split_package_specifier(specifier);
+    if NODE_BUILTINS.contains(&name) {
+        (format!("node:{}", name), ExternalKind::Builtin)
+    } else {
+        (name.to_string(), ExternalKind::Package)
+    }
+}
+
+/**
+ * Returns the DefinitelyTyped package of `name`: `@types/node` for `node` and
+ * `@types/scope__pkg` for `@scope/pkg`.
+ */
+pub fn types_package(name: &str) -> String {
+    match name.strip_prefix('@') {
+        Some(scoped) => format!("@types/{}", scoped.replacen('/', "__", 1)),
+        None => format!("@types/{}", name),
+    }
+}
+
+impl PackageJson {
+    pub fn name(&self) -> Option<&str> {
+        self.json.get("name").and_then(JsonValue::as_str)
+    }
+
+    /**
+     * Returns the target of `subpath`, such as `.` or `./utils`, in the `exports`
+     * map, or None when the package has no `exports` or does not export it.
+     */
+    pub fn resolve_exports(&self, subpath: &str, conditions: &[&str]) -> Option<String> {
+        let exports = self.json.get("exports")?;
+
+        // `"exports": "./index.js"` and `"exports": { "import": ... }` only export `.`
+        let is_subpath_map = exports
+            .as_object()
+            .is_some_and(|members| members.iter().any(|(key, _)| key.starts_with('.')));
+        if !is_subpath_map {
+            return (subpath == ".")
+                .then(|| resolve_target(exports, "", conditions))
+                .flatten();
+        }
+
+        resolve_map(exports, subpath, conditions)
+    }
+
+    /**
+     * Returns the target of a `#internal` specifier in the `imports` map.
+     */
+    pub fn resolve_imports(&self, specifier: &str, conditions: &[&str]) -> Option<String> {
+        resolve_map(self.json.get("imports")?, specifier, conditions)
+    }
+}
+
+/**
+ * Looks `key` up in a subpath map, where keys may hold one `*`: an exact key wins,
+ * then the pattern with the longest prefix before its `*`.
+ */
+fn resolve_map(map: &JsonValue, key: &str, conditions: &[&str]) -> Option<String> {
+    if let Some(target) = map.get(key) {
+        return resolve_target(target, "", conditions);
+    }
+
+    let (_, target, matched) = map
+        .as_object()?
+        .iter()
+        .filter_map(|(pattern, target)| {
+            let (prefix, suffix) = pattern.split_once('*')?;
+            let matched = key.strip_prefix(prefix)?.strip_suffix(suffix)?;
+            Some((prefix.len(), target, matched))
+        })
+        .max_by_key(|(prefix_len, _, _)| *prefix_len)?;
+
+    resolve_target(target, matched, conditions)
+}
+
+/**
+ * Returns the path a target resolves to with `matched` in place of its `*`: a
+ * string, the first condition of an object that is active, or the first entry of
+ * an array that resolves. `null` excludes the subpath.
+ */
+fn resolve_target(target: &JsonValue, matched: &str, conditions: &[&str]) -> Option<String> {
+    match target {
+        JsonValue::String(target) => Some(target.replace('*', matched)),
+        JsonValue::Array(targets) => targets
+            .iter()
+            .find_map(|target| resolve_target(target, matched, conditions)),
+        JsonValue::Object(members) => members
+            .iter()
+            .filter(|(condition, _)| {
+                condition == "default" || conditions.contains(&condition.as_str())
+            })
+            .find_map(|(_, target)| resolve_target(target, matched, conditions)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+fn test_package(json: &str) -> PackageJson {
+    PackageJson {
+        dir: PathBuf::from("/pkg"),
+        json: parse_json(json).unwrap(),
+    }
+}
+
+#[test]
+fn test_split_package_specifier() {
+    assert_eq!(
+        split_package_specifier("lodash"),
+        ("lodash", ".".to_string())
+    );
+    assert_eq!(
+        split_package_specifier("lodash/fp"),
+        ("lodash", "./fp".to_string())
+    );
+    assert_eq!(
+        split_package_specifier("@scope/pkg/sub/path"),
+        ("@scope/pkg", "./sub/path".to_string())
+    );
+    assert_eq!(
+        split_package_specifier("@scope/pkg"),
+        ("@scope/pkg", ".".to_string())
+    );
+}
+
+#[test]
+fn test_external_module() {
+    let cases = [
+        ("lodash", "lodash", ExternalKind::Package),
+        ("lodash/fp", "lodash", ExternalKind::Package),
+        ("@scope/pkg/sub", "@scope/pkg", ExternalKind::Package),
+        ("fs", "node:fs", ExternalKind::Builtin),
+        ("fs/promises", "node:fs", ExternalKind::Builtin),
+        ("node:path", "node:path", ExternalKind::Builtin),
+        ("node:test", "node:test", ExternalKind::Builtin),
+        ("events-emitter", "events-emitter", ExternalKind::Package),
+    ];
+
+    for (specifier, name, kind) in cases {
+        assert_eq!(
+            external_module(specifier),
+            (name.to_string(), kind),
+            "{}",
+            specifier
+        );
+    }
+}
+
+#[test]
+fn test_resolve_exports() {
+    let package = test_package(
+        r#"{
+        "name": "@org/utils",
+        "exports": {
+            ".": {
+                "types": "./src/index.ts",
+                "import": "./dist/index.mjs",
+                "require": "./dist/index.cjs"
+            },
+            "./features/*": {
+                "development": "./src/features/*.ts",
+                "default": "./dist/features/*.js"
+            },
+            "./features/internal/*": null,
+            "./legacy": ["./missing-condition-first", "./dist/legacy.js"],
+            "./package.json": "./package.json"
+        }
+    }"#,
+    );
+
+    assert_eq!(package.name(), Some("@org/utils"));
+    assert_eq!(
+        package.resolve_exports(".", DEFAULT_CONDITIONS).as_deref(),
+        Some("./src/index.ts")
+    );
+    assert_eq!(
+        package.resolve_exports(".", &["require"]).as_deref(),
+        Some("./dist/index.cjs")
+    );
+    assert_eq!(
+        package
+            .resolve_exports("./features/date", DEFAULT_CONDITIONS)
+            .as_deref(),
+        Some("./dist/features/date.js")
+    );
+    assert_eq!(
+        package
+            .resolve_exports("./features/date", &["development"])
+            .as_deref(),
+        Some("./src/features/date.ts")
+    );
+    assert_eq!(
+        package.resolve_exports("./features/internal/secret", DEFAULT_CONDITIONS),
+        None
+    );
+    assert_eq!(
+        package
+            .resolve_exports("./legacy", DEFAULT_CONDITIONS)
+            .as_deref(),
+        Some("./missing-condition-first")
+    );
+    assert_eq!(
+        package.resolve_exports("./missing", DEFAULT_CONDITIONS),
+        None
+    );
+
+    let sugar =
+        test_package(r#"{ "exports": { "browser": "./browser.js", "default": "./node.js" } }"#);
+    assert_eq!(
+        sugar.resolve_exports(".", &["browser"]).as_deref(),
+        Some("./browser.js")
+    );
+    assert_eq!(
+        sugar.resolve_exports(".", DEFAULT_CONDITIONS).as_deref(),
+        Some("./node.js")
+    );
+    assert_eq!(sugar.resolve_exports("./other", DEFAULT_CONDITIONS), None);
+}
+
+#[test]
+fn test_resolve_imports() {
+    let package = test_package(
+        r##"{
+        "imports": {
+            "#config": { "development": "./src/config.dev.ts", "default": "./src/config.ts" },
+            "#internal/*": "./src/internal/*.ts",
+            "#dep": "some-package"
+        }
+    }"##,
+    );
+
+    assert_eq!(
+        package
+            .resolve_imports("#config", DEFAULT_CONDITIONS)
+            .as_deref(),
+        Some("./src/config.ts")
+    );
+    assert_eq!(
+        package
+            .resolve_imports("#internal/db/client", DEFAULT_CONDITIONS)
+            .as_deref(),
+        Some("./src/internal/db/client.ts")
+    );
+    assert_eq!(
+        package
+            .resolve_imports("#dep", DEFAULT_CONDITIONS)
+            .as_deref(),
+        Some("some-package")
+    );
+    assert_eq!(
+        package.resolve_imports("#missing", DEFAULT_CONDITIONS),
+        None
+    );
+}
@@ -1,189 +1,933 @@
-use crate::structs::{TsFile, TsImport, TsImportSource};
+use crate::lexer::{Lexer, Span, Token, TokenKind};
+#[cfg(test)]
+use crate::structs::ModuleSystem;
+use crate::structs::{
+    ImportAttribute, ImportSpecifier, TsExport, TsExportKind, TsFile, TsImport, TsImportKind,
+    TsImportSource, UnresolvableImport,
+};
 use std::{
     fs::{self, canonicalize},
     path::PathBuf,
 };
 
+/**
+ * Parses the file at `f_path`. Returns None when it cannot be read as UTF-8 text.
+ */
 pub fn find_imported_files(f_path: &PathBuf) -> Option<TsFile> {
-    let mut imports: Vec<TsImport> = Vec::new();
-
     let file_name = f_path.file_name()?.to_str()?;
-    let data = fs::read_to_string(f_path).expect("Unable to read file");
-    let import_lines = get_import_lines(&data);
+    let data = fs::read_to_string(f_path).ok()?;
+    let jsx = f_path
+        .extension()
+        .is_some_and(|ext| ext == "tsx" || ext == "jsx");
 
-    for line in import_lines {
-        if let Some(imp) = parse_import(line.to_owned()) {
-            // use origin
-            imports.push(imp);
-        } else {
-            println!(
-                "ERROR reading line: {}",
-                f_path.to_owned().as_os_str().to_str().unwrap()
-            );
-        }
+    Some(parse_ts_file(
+        &data,
+        jsx,
+        file_name.to_string(),
+        String::from(canonicalize(f_path).ok()?.to_str()?),
+    ))
+}
+
+pub fn parse_ts_file(data: &str, jsx: bool, file_name: String, relative_path: String) -> TsFile {
+    let parsed = parse_file(data, jsx);
+
+    TsFile {
+        file_name,
+        imports: parsed.imports,
+        exports: parsed.exports,
+        unresolvable_imports: parsed.unresolvable_imports,
+        commonjs_exports: parsed.commonjs_exports,
+        relative_path,
     }
+}
+
+#[derive(Debug, Default)]
+struct ParsedFile {
+    imports: Vec<TsImport>,
+    exports: Vec<TsExport>,
+    // `import()` and `require()` calls whose argument is not a string literal
+    unresolvable_imports: Vec<UnresolvableImport>,
+    // assigns to `module.exports` or `exports`
+    commonjs_exports: bool,
+}
 
-    Some(TsFile {
-        file_name: file_name.to_string(),
-        imports,
-        relative_path: String::from(canonicalize(f_path).unwrap().to_str().unwrap()),
-    })
-
-    // let mut imports = Vec::new();
-}
-
-fn get_import_lines(data: &str) -> Vec<String> {
-    let mut imports = Vec::new();
-    let mut inside_import = false;
-    let mut import_start_index = 0;
-    let mut braces_count = 0;
-    // clean special chars like: ×
-    let data_cleaned = data
-        .replace(|c: char| !c.is_ascii(), "")
-        .as_str()
-        .to_owned();
-    let mut inside_comment = false;
-    // let _ = String::from_utf8(Vec::from(data.as_bytes())).expect("Found invalid UTF-8");
-
-    for (i, c) in data_cleaned.chars().enumerate() {
-        if data_cleaned[i..].starts_with("//") {
-            inside_comment = true;
-        } else if inside_comment {
-            if c == '\n' {
-                inside_comment = false;
+/**
+ * Finds and parses every static import declaration, `export ... from` re-export,
+ * dynamic `import()`, CommonJS `require()` and `/// <reference />` directive in
+ * `data` by walking the lexer tokens.
+ */
+fn parse_file(data: &str, jsx: bool) -> ParsedFile {
+    let mut parsed = ParsedFile::default();
+    let mut lexer = Lexer::new(data, data.len()).with_jsx(jsx);
+    // kind of the last significant token, used to tell `import` declarations
+    // apart from member accesses such as `foo.import`
+    let mut prev_kind = TokenKind::TokenSemicolon;
+    // whether the last significant token was `function`, so a `require(` names
+    // the function being declared
+    let mut prev_function = false;
+    // triple-slash directives are only honored before the first statement
+    let mut in_header = true;
+    // JSDoc of the next statement, kept for its exports
+    let mut doc = None;
+
+    while let Some(token) = lexer.next() {
+        if token.token_kind.is_comment() {
+            if token.token_kind == TokenKind::TokenDocComment {
+                doc = token.doc_text();
             }
-        } else if !inside_import && data_cleaned[i..].starts_with("import ") {
-            inside_import = true;
-            import_start_index = i;
-            braces_count = 0;
-        } else if inside_import {
-            if c == '{' {
-                braces_count += 1;
-            } else if c == '}' {
-                braces_count -= 1;
+            if in_header && token.token_kind == TokenKind::TokenComment {
+                if let Some(import) = parse_triple_slash_directive(&token) {
+                    parsed.imports.push(import);
+                }
             }
+            continue;
+        }
+        in_header = false;
+
+        match token.token_kind {
+            TokenKind::TokenKeyword
+                if token.get_text() == "import" && prev_kind != TokenKind::TokenDot =>
+            {
+                let next_kind = lexer.peek().map(|next| next.token_kind.clone());
+
+                // `import.meta` is an expression
+                if next_kind == Some(TokenKind::TokenDot) {
+                    continue;
+                }
+
+                if next_kind == Some(TokenKind::TokenOpenParen) {
+                    parse_import_call(
+                        &mut lexer,
+                        &mut parsed,
+                        data,
+                        token.span,
+                        TsImportKind::Dynamic,
+                    );
+                    continue;
+                }
 
-            if braces_count == 0 && c == '\n' {
-                inside_import = false;
-                let import_str = &data[import_start_index..=i];
-                imports.push(import_str.trim().replace('\n', ""));
+                let checkpoint = lexer.checkpoint();
+                if let Some(import) = parse_import_declaration(&mut lexer, token.span) {
+                    parsed.imports.push(import);
+                } else {
+                    // not a declaration, the tokens after `import` are scanned as usual
+                    lexer.rewind(checkpoint);
+                }
+            }
+            TokenKind::TokenKeyword
+                if token.get_text() == "export" && prev_kind != TokenKind::TokenDot =>
+            {
+                let checkpoint = lexer.checkpoint();
+                if let Some(import) = parse_reexport_declaration(&mut lexer, token.span) {
+                    parsed.imports.push(import);
+                } else {
+                    // a local export, the tokens after `export` are scanned as usual
+                    lexer.rewind(checkpoint);
+                    let checkpoint = lexer.checkpoint();
+                    for (name, kind) in parse_export_declaration(&mut lexer).unwrap_or_default() {
+                        parsed.exports.push(TsExport {
+                            name,
+                            kind,
+                            span: token.span,
+                            doc: doc.clone(),
+                        });
+                    }
+                    lexer.rewind(checkpoint);
+                }
+            }
+            TokenKind::TokenKeyword
+                if token.get_text() == "require"
+                    && prev_kind != TokenKind::TokenDot
+                    && !prev_function
+                    && lexer.peek_is(0, TokenKind::TokenOpenParen) =>
+            {
+                parse_import_call(
+                    &mut lexer,
+                    &mut parsed,
+                    data,
+                    token.span,
+                    TsImportKind::Require,
+                );
+                continue;
+            }
+            // `module.exports = ...`
+            TokenKind::TokenKeyword
+                if token.get_text() == "module"
+                    && prev_kind != TokenKind::TokenDot
+                    && lexer.peek_is(0, TokenKind::TokenDot)
+                    && peek_is_word(&mut lexer, 1, "exports") =>
+            {
+                parsed.commonjs_exports = true;
             }
+            // `exports.name = ...`, `exports['name'] = ...`, but not a declaration
+            // such as `const exports = ...`
+            TokenKind::TokenSymbol
+                if token.get_text() == "exports"
+                    && prev_kind != TokenKind::TokenDot
+                    && prev_kind != TokenKind::TokenKeyword
+                    && (lexer.peek_is(0, TokenKind::TokenDot)
+                        || lexer.peek_is(0, TokenKind::TokenOpenBracket)
+                        || lexer.peek_is(0, TokenKind::TokenEq)) =>
+            {
+                parsed.commonjs_exports = true;
+            }
+            _ => {}
         }
-    }
 
-    if inside_import {
-        let import_str = &data[import_start_index..];
-        imports.push(import_str.trim().replace('\n', ""));
+        prev_function = &token.text[..token.text_len] == "function";
+        prev_kind = token.token_kind;
+        doc = None;
     }
 
-    imports
+    parsed
 }
 
-// basic import parser
-fn parse_import(line: String) -> Option<TsImport> {
-    let mut tokenized_import = tokenize_import(line.as_str());
-    tokenized_import.reverse();
-    let mut import_type = TsImportSource::Package;
+fn peek_is_word(lexer: &mut Lexer, n: usize, word: &str) -> bool {
+    lexer
+        .peek_significant(n)
+        .is_some_and(|token| is_identifier(&token) && token.get_text() == word)
+}
 
-    if tokenized_import.is_empty() {
-        println!("ERROR: tokenized length 0 for: {}", line.as_str());
-        return None;
+/**
+ * Contextual keywords such as `type`, `from` or `as` are valid binding names, so
+ * both symbols and keywords count as identifiers.
+ */
+fn is_identifier(token: &Token) -> bool {
+    matches!(
+        token.token_kind,
+        TokenKind::TokenSymbol | TokenKind::TokenKeyword
+    )
+}
+
+fn expect_identifier(lexer: &mut Lexer) -> Option<String> {
+    let token = lexer.next_significant()?;
+    is_identifier(&token).then(|| token.get_text())
+}
+
+/**
+ * Names of exports can also be strings: `import { "string name" as alias }`.
+ */
+fn expect_module_export_name(lexer: &mut Lexer) -> Option<String> {
+    let token = lexer.next_significant()?;
+
+    match token.token_kind {
+        TokenKind::TokenString => token.value(),
+        _ if is_identifier(&token) => Some(token.get_text()),
+        _ => None,
     }
+}
+
+fn expect_word(lexer: &mut Lexer, word: &str) -> Option<()> {
+    let token = lexer.next_significant()?;
+    (is_identifier(&token) && token.get_text() == word).then_some(())
+}
+
+/**
+ * Parses an import declaration after its `import` keyword, whose span is `start`:
+ *
+ * import 'module';
+ * import defaultExport from 'module';
+ * import * as name from 'module';
+ * import { a, b as c, type D } from 'module';
+ * import defaultExport, { a } from 'module';
+ * import type { A } from 'module';
+ * import name = require('module');
+ *
+ * Returns None when the keyword does not start an import declaration
+ * (`import(...)`, `import.meta`, `{ import: ... }`) or the declaration is malformed.
+ */
+fn parse_import_declaration(lexer: &mut Lexer, start: Span) -> Option<TsImport> {
+    let mut import = TsImport::default();
 
-    if tokenized_import[0].starts_with('.') {
-        import_type = TsImportSource::Local
+    if lexer.peek_is(0, TokenKind::TokenString) {
+        import.side_effect_only = true;
+    } else {
+        // `import type from 'module'`, `import type, { a } from 'module'` and
+        // `import type = require('module')` import a default export named `type`
+        let type_is_binding = lexer.peek_is(1, TokenKind::TokenComma)
+            || lexer.peek_is(1, TokenKind::TokenEq)
+            || (peek_is_word(lexer, 1, "from") && lexer.peek_is(2, TokenKind::TokenString));
+        if peek_is_word(lexer, 0, "type") && !type_is_binding {
+            lexer.next_significant();
+            import.type_only = true;
+        }
+
+        if lexer.peek_is(1, TokenKind::TokenEq) {
+            return parse_import_equals(lexer, import, start);
+        }
+
+        parse_import_clause(lexer, &mut import)?;
+        expect_word(lexer, "from")?;
     }
 
-    let source = tokenized_import[0].to_owned();
+    parse_module_specifier(lexer, &mut import, start)?;
+    parse_import_attributes(lexer, &mut import)?;
+
+    Some(import)
+}
 
-    let mut source_str = source.replace('\'', "");
-    let len = source_str.len();
+/**
+ * Parses a re-export after its `export` keyword, whose span is `start`:
+ *
+ * export * from 'module';
+ * export * as name from 'module';
+ * export { a, b as c, default } from 'module';
+ * export type { A } from 'module';
+ *
+ * Returns None for every other export, including `export { a }` without `from`.
+ */
+fn parse_reexport_declaration(lexer: &mut Lexer, start: Span) -> Option<TsImport> {
+    let mut import = TsImport {
+        kind: TsImportKind::ReExport,
+        ..Default::default()
+    };
 
-    if source_str.ends_with("..") {
-        // source_str.push_str("/index")
-        source_str.replace_range(len - 2..len, "index");
-        println!("Str: {}", source_str);
+    if peek_is_word(lexer, 0, "type")
+        && (lexer.peek_is(1, TokenKind::TokenStar) || lexer.peek_is(1, TokenKind::TokenOpenCurly))
+    {
+        lexer.next_significant();
+        import.type_only = true;
     }
 
-    if source_str.ends_with('.') {
-        source_str.replace_range(len - 1..len, "index");
-        println!("Str: {}", source_str);
+    let token = lexer.next_significant()?;
+    match token.token_kind {
+        TokenKind::TokenStar if peek_is_word(lexer, 0, "as") => {
+            lexer.next_significant();
+            import.namespace_binding = Some(expect_module_export_name(lexer)?);
+        }
+        TokenKind::TokenStar => import.export_all = true,
+        TokenKind::TokenOpenCurly => import.named = parse_named_imports(lexer)?,
+        _ => return None,
     }
 
-    source_str.push_str(".ts");
+    expect_word(lexer, "from")?;
+    parse_module_specifier(lexer, &mut import, start)?;
+    parse_import_attributes(lexer, &mut import)?;
 
-    Some(TsImport {
-        import_source: import_type,
-        source: source_str,
-    })
+    Some(import)
 }
 
-fn tokenize_import(import_str: &str) -> Vec<String> {
-    let mut tokens: Vec<String> = Vec::new();
+/**
+ * Parses the names declared by a local export after its `export` keyword:
+ *
+ * export const a = 1;
+ * export async function b() {}
+ * export abstract class C {}
+ * export interface D {}
+ * export type E = string;
+ * export const enum F {}
+ * export namespace G {}
+ * export default H;
+ * export { i, j as k };
+ * export = L;
+ *
+ * Every declarator of a variable statement is read, with the names bound by
+ * destructuring patterns.
+ */
+fn parse_export_declaration(lexer: &mut Lexer) -> Option<Vec<(String, TsExportKind)>> {
+    if peek_is_word(lexer, 0, "declare") {
+        lexer.next_significant();
+    }
 
-    let parts = import_str.split(' ');
-    let parts_collection: Vec<&str> = parts.collect();
-    let mut parts_collection_str: Vec<String> = parts_collection
-        .iter()
-        .map(|&x| x.trim_end_matches(';').replace(['\'', '"'], ""))
-        .collect();
+    // `export type { A }`
+    if peek_is_word(lexer, 0, "type") && lexer.peek_is(1, TokenKind::TokenOpenCurly) {
+        lexer.next_significant();
+    }
+
+    let token = lexer.next_significant()?;
+    if token.token_kind == TokenKind::TokenEq {
+        return Some(vec![("default".to_string(), TsExportKind::Assignment)]);
+    }
+    if token.token_kind == TokenKind::TokenOpenCurly {
+        return Some(
+            parse_named_imports(lexer)?
+                .into_iter()
+                .map(|specifier| (specifier.local, TsExportKind::Named))
+                .collect(),
+        );
+    }
+    if !is_identifier(&token) {
+        return None;
+    }
+
+    let kind = match token.get_text().as_str() {
+        "default" => return Some(vec![("default".to_string(), TsExportKind::Default)]),
+        "const" if peek_is_word(lexer, 0, "enum") => {
+            lexer.next_significant();
+            TsExportKind::Enum
+        }
+        "const" | "let" | "var" => {
+            let mut names = Vec::new();
+            loop {
+                match lexer.peek_significant(0)?.token_kind {
+                    TokenKind::TokenOpenCurly | TokenKind::TokenOpenBracket => {
+                        names.extend(parse_binding_pattern(lexer)?)
+                    }
+                    _ => names.push(expect_identifier(lexer)?),
+                }
+                if !skip_to_next_declarator(lexer) {
+                    break;
+                }
+            }
+            return Some(
+                names
+                    .into_iter()
+                    .map(|name| (name, TsExportKind::Variable))
+                    .collect(),
+            );
+        }
+        "async" => {
+            expect_word(lexer, "function")?;
+            TsExportKind::Function
+        }
+        "function" => TsExportKind::Function,
+        "abstract" => {
+            expect_word(lexer, "class")?;
+            TsExportKind::Class
+        }
+        "class" => TsExportKind::Class,
+        "interface" => TsExportKind::Interface,
+        "type" => TsExportKind::TypeAlias,
+        "enum" => TsExportKind::Enum,
+        "namespace" | "module" => TsExportKind::Namespace,
+        _ => return None,
+    };
 
-    if parts_collection.len() == 2 {
-        parts_collection_str.drain(0..1);
-        return parts_collection_str;
+    // `export function* generator() {}`
+    if kind == TsExportKind::Function && lexer.peek_is(0, TokenKind::TokenStar) {
+        lexer.next_significant();
     }
 
-    // Remove leading "import " keyword and trailing semicolon (if any)
-    let import_str = import_str
-        .trim_end()
-        .trim_start_matches("import ")
-        .trim_end_matches(';');
-
-    // Split import string by whitespace
-    let parts: Vec<&str> = import_str.split_whitespace().collect();
-
-    // Loop through parts and tokenize
-    let mut i = 0;
-    while i < parts.len() {
-        match parts[i] {
-            // Handle default import syntax
-            "*" => {
-                tokens.push("*".to_owned());
-                i += 1;
+    Some(vec![(expect_identifier(lexer)?, kind)])
+}
+
+/**
+ * Words that continue an expression from the previous line, so no semicolon is
+ * inserted before them.
+ */
+static CONTINUING_WORDS: &[&str] = &["as", "satisfies", "in", "instanceof"];
+
+/**
+ * Whether a semicolon is inserted between `prev` and `token` when `token` starts
+ * a new line: `prev` ends an expression and `token` cannot continue it.
+ */
+fn ends_statement_at_line_break(prev: &Token, token: &Token) -> bool {
+    let prev_ends_expression = match prev.token_kind {
+        TokenKind::TokenSymbol
+        | TokenKind::TokenString
+        | TokenKind::TokenTemplate
+        | TokenKind::TokenNumber
+        | TokenKind::TokenRegex
+        | TokenKind::TokenCloseParen
+        | TokenKind::TokenCloseBracket
+        | TokenKind::TokenCloseCurly
+        | TokenKind::TokenPlusPlus
+        | TokenKind::TokenMinusMinus => true,
+        TokenKind::TokenKeyword => {
+            ["this", "true", "false", "null", "super"].contains(&prev.get_text().as_str())
+        }
+        _ => false,
+    };
+    let token_starts_statement = match token.token_kind {
+        TokenKind::TokenSymbol | TokenKind::TokenKeyword => {
+            !CONTINUING_WORDS.contains(&token.get_text().as_str())
+        }
+        TokenKind::TokenString
+        | TokenKind::TokenNumber
+        | TokenKind::TokenOpenCurly
+        | TokenKind::TokenPlusPlus
+        | TokenKind::TokenMinusMinus => true,
+        _ => false,
+    };
+
+    prev_ends_expression && token_starts_statement
+}
+
+/**
+ * Skips the type annotation and initializer of a variable declarator, up to the
+ * `,` before the next declarator or the end of the statement. Returns whether
+ * another declarator follows. Type arguments such as `Map<string, number>` are
+ * skipped as brackets, and the statement also ends at a line break where a
+ * semicolon is inserted.
+ */
+fn skip_to_next_declarator(lexer: &mut Lexer) -> bool {
+    let mut depth = 0;
+    let mut angle_depth: usize = 0;
+    let mut prev: Option<Token> = None;
+
+    while let Some(token) = lexer.next_significant() {
+        let at_top = depth == 0 && angle_depth == 0;
+        let ends_statement = prev.as_ref().is_some_and(|prev| {
+            token.span.line > prev.span.line && ends_statement_at_line_break(prev, &token)
+        });
+        prev = Some(token.clone());
+
+        match token.token_kind {
+            _ if at_top && ends_statement => return false,
+            TokenKind::TokenComma if at_top => return true,
+            TokenKind::TokenSemicolon if depth == 0 => return false,
+            TokenKind::TokenOpenCurly | TokenKind::TokenOpenBracket | TokenKind::TokenOpenParen => {
+                depth += 1
             }
-            // Handle named import syntax
-            "{" => {
-                i += 1;
-                while i < parts.len() && parts[i] != "}" {
-                    let rep = parts[i].replace(',', "");
-                    tokens.push(rep.to_owned());
-
-                    i += 1;
+            TokenKind::TokenCloseCurly
+            | TokenKind::TokenCloseBracket
+            | TokenKind::TokenCloseParen => {
+                if depth == 0 {
+                    return false;
                 }
-                i += 1;
+                depth -= 1;
             }
-            // Handle from keyword and module path
-            "from" => {
-                i += 1;
-                if i < parts.len() {
-                    let rep = parts[i].replace(['\'', '"'], "");
-                    tokens.push(rep);
-                    break;
+            TokenKind::TokenLt if depth == 0 => angle_depth += 1,
+            // nested type arguments close with `>>` and `>>>`, and `>=` when an
+            // initializer directly follows, `a: Array<T>= []`
+            TokenKind::TokenGt | TokenKind::TokenGtEq if depth == 0 => {
+                angle_depth = angle_depth.saturating_sub(1)
+            }
+            TokenKind::TokenShr | TokenKind::TokenShrEq if depth == 0 => {
+                angle_depth = angle_depth.saturating_sub(2)
+            }
+            TokenKind::TokenUShr | TokenKind::TokenUShrEq if depth == 0 => {
+                angle_depth = angle_depth.saturating_sub(3)
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/**
+ * Returns the names bound by an object or array destructuring pattern, such as
+ * `a`, `c` and `rest` in `{ a, b: c, ...rest }`, skipping property keys and default
+ * values.
+ */
+fn parse_binding_pattern(lexer: &mut Lexer) -> Option<Vec<String>> {
+    let mut names = Vec::new();
+    let mut depth = 0;
+    // depth of the default value being skipped, e.g. `= 1` in `{ a = 1 }`
+    let mut default_depth = None;
+
+    loop {
+        let token = lexer.next_significant()?;
+
+        match token.token_kind {
+            TokenKind::TokenOpenCurly | TokenKind::TokenOpenBracket | TokenKind::TokenOpenParen => {
+                depth += 1
+            }
+            TokenKind::TokenCloseCurly
+            | TokenKind::TokenCloseBracket
+            | TokenKind::TokenCloseParen => {
+                if default_depth == Some(depth) {
+                    default_depth = None;
+                }
+                depth -= 1;
+                if depth == 0 {
+                    return Some(names);
                 }
             }
-            _ => {
-                i += 1;
+            TokenKind::TokenComma if default_depth == Some(depth) => default_depth = None,
+            TokenKind::TokenEq if default_depth.is_none() => default_depth = Some(depth),
+            _ if default_depth.is_none()
+                && is_identifier(&token)
+                && !lexer.peek_is(0, TokenKind::TokenColon) =>
+            {
+                names.push(token.get_text())
+            }
+            _ => {}
+        }
+    }
+}
+
+/**
+ * Parses a dynamic import or a `require` call after its `import`/`require` keyword,
+ * whose span is `start`, and adds it to `parsed`:
+ *
+ * import('./module')
+ * import(`./module`)
+ * import('./module', { with: { type: 'json' } })
+ * require('./module')
+ *
+ * When the module can only be known at runtime, e.g. `import(`./pages/${name}`)` or
+ * `require(path)`, the call is added as an unresolvable import.
+ */
+fn parse_import_call(
+    lexer: &mut Lexer,
+    parsed: &mut ParsedFile,
+    data: &str,
+    start: Span,
+    kind: TsImportKind,
+) {
+    let checkpoint = lexer.checkpoint();
+
+    match parse_call_arguments(lexer, data, start, kind) {
+        Some(Ok(import)) => parsed.imports.push(import),
+        Some(Err(unresolvable)) => {
+            // the arguments can hold more imports, scan them as usual
+            lexer.rewind(checkpoint);
+            parsed.unresolvable_imports.push(unresolvable);
+        }
+        None => lexer.rewind(checkpoint),
+    }
+}
+
+/**
+ * Returns an unresolvable import, with the source text of the call, when the first
+ * argument is not a string literal. Returns None when the parentheses are not
+ * closed, or when they hold the parameters of a method or function declaration
+ * such as `require(id: string): any` or `require(id) {}`.
+ */
+fn parse_call_arguments(
+    lexer: &mut Lexer,
+    data: &str,
+    start: Span,
+    kind: TsImportKind,
+) -> Option<Result<TsImport, UnresolvableImport>> {
+    lexer.next_significant()?;
+
+    let argument = lexer.next_significant()?;
+    let specifier = argument.value().filter(|_| {
+        matches!(
+            argument.token_kind,
+            TokenKind::TokenString | TokenKind::TokenTemplate
+        )
+    });
+    let literal_argument = specifier.is_some()
+        && (lexer.peek_is(0, TokenKind::TokenCloseParen)
+            || lexer.peek_is(0, TokenKind::TokenComma));
+    // `(id: string)` and `(id?: string)` are typed parameters
+    let typed_parameter = is_identifier(&argument)
+        && (lexer.peek_is(0, TokenKind::TokenColon) || lexer.peek_is(0, TokenKind::TokenQuestion));
+
+    // `import('./data.json', { with: { type: 'json' } })`
+    let mut attributes = Vec::new();
+    if literal_argument
+        && lexer.peek_is(0, TokenKind::TokenComma)
+        && lexer.peek_is(1, TokenKind::TokenOpenCurly)
+        && (peek_is_word(lexer, 2, "with") || peek_is_word(lexer, 2, "assert"))
+        && lexer.peek_is(3, TokenKind::TokenColon)
+        && lexer.peek_is(4, TokenKind::TokenOpenCurly)
+    {
+        for _ in 0..5 {
+            lexer.next_significant();
+        }
+        // the rest of the options object is skipped with the call
+        attributes = parse_attribute_entries(lexer)
+            .map(|(attributes, _)| attributes)
+            .unwrap_or_default();
+    }
+
+    // find the closing parenthesis of the call
+    let mut parens_count = 0;
+    let mut end = argument;
+    loop {
+        match end.token_kind {
+            TokenKind::TokenOpenParen => parens_count += 1,
+            TokenKind::TokenCloseParen if parens_count == 0 => break,
+            TokenKind::TokenCloseParen => parens_count -= 1,
+            _ => {}
+        }
+        end = lexer.next_significant()?;
+    }
+
+    let is_declaration = !literal_argument
+        && (lexer.peek_is(0, TokenKind::TokenOpenCurly)
+            || (typed_parameter && lexer.peek_is(0, TokenKind::TokenColon)));
+    if is_declaration {
+        return None;
+    }
+
+    let span = Span {
+        end: end.span.end,
+        ..start
+    };
+
+    let Some(specifier) = specifier.filter(|_| literal_argument) else {
+        return Some(Err(UnresolvableImport {
+            expression: data[span.start..span.end].to_string(),
+            span,
+        }));
+    };
+
+    let mut import = TsImport {
+        kind,
+        ..Default::default()
+    };
+    set_import_specifier(&mut import, specifier, span);
+    import.attributes = attributes;
+
+    Some(Ok(import))
+}
+
+/**
+ * Parses the TypeScript form of a CommonJS import after the `import` keyword:
+ *
+ * import fs = require('fs');
+ *
+ * Returns None for namespace aliases such as `import Alias = Some.Namespace;`.
+ */
+fn parse_import_equals(lexer: &mut Lexer, mut import: TsImport, start: Span) -> Option<TsImport> {
+    import.kind = TsImportKind::Require;
+    import.default_binding = Some(expect_identifier(lexer)?);
+    lexer.next_significant();
+    expect_word(lexer, "require")?;
+
+    if lexer.next_significant()?.token_kind != TokenKind::TokenOpenParen {
+        return None;
+    }
+    parse_module_specifier(lexer, &mut import, start)?;
+
+    let close_paren = lexer.next_significant()?;
+    if close_paren.token_kind != TokenKind::TokenCloseParen {
+        return None;
+    }
+    import.span.end = close_paren.span.end;
+
+    Some(import)
+}
+
+/**
+ * Parses the module specifier string that ends an import or re-export and fills in
+ * where the import points to.
+ */
+fn parse_module_specifier(lexer: &mut Lexer, import: &mut TsImport, start: Span) -> Option<()> {
+    let specifier = lexer.next_significant()?;
+    if specifier.token_kind != TokenKind::TokenString {
+        return None;
+    }
+
+    let span = Span {
+        end: specifier.span.end,
+        ..start
+    };
+    set_import_specifier(import, specifier.value()?, span);
+
+    Some(())
+}
+
+/**
+ * Parses the import attributes after a module specifier, if any:
+ *
+ * import data from './data.json' with { type: 'json' };
+ * import data from './data.json' assert { type: 'json' };
+ */
+fn parse_import_attributes(lexer: &mut Lexer, import: &mut TsImport) -> Option<()> {
+    if !(peek_is_word(lexer, 0, "with") || peek_is_word(lexer, 0, "assert"))
+        || !lexer.peek_is(1, TokenKind::TokenOpenCurly)
+    {
+        return Some(());
+    }
+    lexer.next_significant();
+    lexer.next_significant();
+
+    let (attributes, end) = parse_attribute_entries(lexer)?;
+    import.span.end = end.end;
+    import.attributes = attributes;
+
+    Some(())
+}
+
+/**
+ * Parses `key: 'value'` entries up to and including the closing `}` of an
+ * attributes object, returning them with the span of the `}`.
+ */
+fn parse_attribute_entries(lexer: &mut Lexer) -> Option<(Vec<ImportAttribute>, Span)> {
+    let mut attributes = Vec::new();
+
+    loop {
+        if lexer.peek_is(0, TokenKind::TokenCloseCurly) {
+            let close_curly = lexer.next_significant()?;
+            return Some((attributes, close_curly.span));
+        }
+
+        let key = expect_module_export_name(lexer)?;
+        if lexer.next_significant()?.token_kind != TokenKind::TokenColon {
+            return None;
+        }
+        let value = lexer.next_significant()?;
+        if value.token_kind != TokenKind::TokenString {
+            return None;
+        }
+        attributes.push(ImportAttribute {
+            key,
+            value: value.value()?,
+        });
+
+        if lexer.peek_is(0, TokenKind::TokenComma) {
+            lexer.next_significant();
+        } else if !lexer.peek_is(0, TokenKind::TokenCloseCurly) {
+            return None;
+        }
+    }
+}
+
+fn set_import_specifier(import: &mut TsImport, specifier: String, span: Span) {
+    import.import_source = if specifier.starts_with('.') {
+        TsImportSource::Local
+    } else {
+        TsImportSource::Package
+    };
+    import.source = specifier.clone();
+    import.specifier = specifier;
+    import.span = span;
+}
+
+/**
+ * Parses the bindings between `import` and `from`: a default binding, followed by
+ * either a namespace import or a list of named imports.
+ */
+fn parse_import_clause(lexer: &mut Lexer, import: &mut TsImport) -> Option<()> {
+    let token = lexer.peek_significant(0)?;
+
+    if is_identifier(&token) {
+        import.default_binding = expect_identifier(lexer);
+
+        if !lexer.peek_is(0, TokenKind::TokenComma) {
+            return Some(());
+        }
+        lexer.next_significant();
+    }
+
+    let token = lexer.next_significant()?;
+    match token.token_kind {
+        TokenKind::TokenStar => {
+            expect_word(lexer, "as")?;
+            import.namespace_binding = Some(expect_identifier(lexer)?);
+        }
+        TokenKind::TokenOpenCurly => {
+            import.named = parse_named_imports(lexer)?;
+        }
+        _ => return None,
+    }
+
+    Some(())
+}
+
+/**
+ * Parses named imports, or the named exports of a re-export, after the opening `{`
+ * up to and including the closing `}`.
+ */
+fn parse_named_imports(lexer: &mut Lexer) -> Option<Vec<ImportSpecifier>> {
+    let mut named = Vec::new();
+
+    loop {
+        if lexer.peek_is(0, TokenKind::TokenCloseCurly) {
+            lexer.next_significant();
+            return Some(named);
+        }
+
+        // `type` is a modifier unless it is the imported name itself:
+        // `{ type }`, `{ type as t }`
+        let is_type = peek_is_word(lexer, 0, "type")
+            && !lexer.peek_is(1, TokenKind::TokenComma)
+            && !lexer.peek_is(1, TokenKind::TokenCloseCurly)
+            && (!peek_is_word(lexer, 1, "as") || peek_is_word(lexer, 2, "as"));
+        if is_type {
+            lexer.next_significant();
+        }
+
+        let imported = expect_module_export_name(lexer)?;
+
+        let local = if peek_is_word(lexer, 0, "as") {
+            lexer.next_significant();
+            expect_module_export_name(lexer)?
+        } else {
+            imported.clone()
+        };
+
+        named.push(ImportSpecifier {
+            imported,
+            local,
+            is_type,
+        });
+
+        if lexer.peek_is(0, TokenKind::TokenComma) {
+            lexer.next_significant();
+        } else if !lexer.peek_is(0, TokenKind::TokenCloseCurly) {
+            return None;
+        }
+    }
+}
+
+/**
+ * Parses a triple-slash reference directive from a line comment:
+ *
+ * /// <reference path="./globals.d.ts" />
+ * /// <reference types="node" />
+ *
+ * `path` names a file relative to the referencing file, with its extension, and
+ * `types` names a package. Other directives such as `lib` and `no-default-lib` refer
+ * to TypeScript's own declarations and return None.
+ */
+fn parse_triple_slash_directive(token: &Token) -> Option<TsImport> {
+    let text = token.get_text();
+    let directive = text.strip_prefix("///")?.trim();
+    let attributes = directive.strip_prefix("<reference")?;
+
+    let mut import = TsImport {
+        kind: TsImportKind::TypeReference,
+        span: token.span,
+        type_only: true,
+        ..Default::default()
+    };
+
+    if let Some(path) = directive_attribute(attributes, "path") {
+        import.import_source = TsImportSource::Local;
+        import.source = path.to_owned();
+        import.specifier = path.to_owned();
+    } else {
+        let package = directive_attribute(attributes, "types")?;
+        import.import_source = TsImportSource::Package;
+        import.source = package.to_owned();
+        import.specifier = package.to_owned();
+    }
+
+    Some(import)
+}
+
+/**
+ * Returns the quoted value of the `name="value"` attribute in `attributes`.
+ */
+fn directive_attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes;
+
+    while let Some(index) = rest.find(name) {
+        let preceded_by_space = rest[..index].ends_with(char::is_whitespace);
+        rest = &rest[index + name.len()..];
+
+        if let Some(value) = rest.trim_start().strip_prefix('=') {
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+            let value = &value[1..];
+            let end = value.find(quote)?;
+
+            if preceded_by_space {
+                return Some(&value[..end]);
             }
         }
     }
 
-    tokens
+    None
+}
+
+#[cfg(test)]
+fn get_import_specifiers(data: &str) -> Vec<String> {
+    parse_file(data, false)
+        .imports
+        .into_iter()
+        .map(|import| import.specifier)
+        .collect()
+}
+
+#[cfg(test)]
+fn parse_single_import(data: &str) -> TsImport {
+    let mut imports = parse_file(data, false).imports;
+    assert_eq!(imports.len(), 1);
+    imports.remove(0)
 }
 
-// test get_import_lines
 #[test]
-fn test_get_import_lines() {
-    let res = get_import_lines(
+fn test_parse_imports() {
+    let res = parse_file(
         "
     import {
         Component
@@ -198,63 +942,641 @@ fn test_get_import_lines() {
 
     const myFunc = function(){};
     ",
-    );
+        false,
+    )
+    .imports;
     assert_eq!(res.len(), 5);
 }
 
-// test tokenize_import
 #[test]
-fn test_tokenize_default_import() {
-    let import_str = "import * as myModule from 'mylib';";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["*", "mylib"]);
+fn test_parse_namespace_import() {
+    let import = parse_single_import("import * as myModule from 'mylib';");
+    assert_eq!(import.namespace_binding.as_deref(), Some("myModule"));
+    assert_eq!(import.specifier, "mylib");
+    assert!(matches!(import.import_source, TsImportSource::Package));
+}
+
+#[test]
+fn test_parse_named_import() {
+    let import = parse_single_import("import { myFunc } from './myfile';");
+    assert_eq!(
+        import.named,
+        vec![ImportSpecifier {
+            imported: "myFunc".to_string(),
+            local: "myFunc".to_string(),
+            is_type: false,
+        }]
+    );
+    assert_eq!(import.specifier, "./myfile");
+    assert_eq!(import.source, "./myfile");
+    assert!(matches!(import.import_source, TsImportSource::Local));
+}
+
+#[test]
+fn test_parse_multiple_named_imports() {
+    let import = parse_single_import("import { myFunc, myVar, } from './myfile';");
+    let names: Vec<&str> = import.named.iter().map(|n| n.imported.as_str()).collect();
+    assert_eq!(names, vec!["myFunc", "myVar"]);
+}
+
+#[test]
+fn test_parse_relative_path_import() {
+    let import = parse_single_import("import { myFunc } from '../mydir/myfile';");
+    assert_eq!(import.specifier, "../mydir/myfile");
+}
+
+#[test]
+fn test_parse_import_with_leading_and_trailing_spaces() {
+    let import = parse_single_import("   import { myFunc } from './myfile';   ");
+    assert_eq!(import.named.len(), 1);
+    assert_eq!(import.specifier, "./myfile");
+}
+
+#[test]
+fn test_parse_default_import() {
+    let import = parse_single_import("import myFunc from 'mylib';");
+    assert_eq!(import.default_binding.as_deref(), Some("myFunc"));
+    assert!(import.named.is_empty());
+    assert_eq!(import.specifier, "mylib");
+}
+
+#[test]
+fn test_parse_whole_module() {
+    let import = parse_single_import("import 'mylib';");
+    assert!(import.side_effect_only);
+    assert_eq!(import.specifier, "mylib");
+}
+
+#[test]
+fn test_parse_default_and_named_aliases() {
+    let import = parse_single_import(
+        "import React, { useState as useS, type FC, default as d } from 'react'",
+    );
+    assert_eq!(import.default_binding.as_deref(), Some("React"));
+    assert!(!import.type_only);
+    assert_eq!(
+        import.named,
+        vec![
+            ImportSpecifier {
+                imported: "useState".to_string(),
+                local: "useS".to_string(),
+                is_type: false,
+            },
+            ImportSpecifier {
+                imported: "FC".to_string(),
+                local: "FC".to_string(),
+                is_type: true,
+            },
+            ImportSpecifier {
+                imported: "default".to_string(),
+                local: "d".to_string(),
+                is_type: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_type_only_imports() {
+    let import = parse_single_import("import type { A } from './a';");
+    assert!(import.type_only);
+    assert_eq!(import.named[0].imported, "A");
+
+    let import = parse_single_import("import type * as ns from './a';");
+    assert!(import.type_only);
+    assert_eq!(import.namespace_binding.as_deref(), Some("ns"));
+
+    // a default import named `type`
+    let import = parse_single_import("import type from './a';");
+    assert!(!import.type_only);
+    assert_eq!(import.default_binding.as_deref(), Some("type"));
+
+    let import = parse_single_import("import { type, type as t } from './a';");
+    assert!(!import.type_only);
+    assert!(import
+        .named
+        .iter()
+        .all(|n| n.imported == "type" && !n.is_type));
+}
+
+#[test]
+fn test_parse_imports_same_line() {
+    let res = get_import_specifiers("const a = 1; import { b } from './b'; import './c';");
+    assert_eq!(res, vec!["./b", "./c"]);
+}
+
+#[test]
+fn test_parse_imports_comment_in_string() {
+    let res = get_import_specifiers(
+        "
+    const url = 'https://example.com';
+    import { a } from './a';
+    const api = \"//api.example.com\"; import { b } from './b';
+    const path = `//${host}/x`;
+    import { c } from './c';
+    ",
+    );
+    assert_eq!(res, vec!["./a", "./b", "./c"]);
+
+    let res = get_import_specifiers("const url = 'https://example.com';\nimport { a } from './a';");
+    assert_eq!(res, vec!["./a"]);
+}
+
+#[test]
+fn test_parse_imports_block_comments() {
+    let res = get_import_specifiers(
+        "#!/usr/bin/env node
+    /*
+    import { old } from './old';
+    */
+    import /* inline */ { a } from './a';
+    ",
+    );
+    assert_eq!(res, vec!["./a"]);
+}
+
+#[test]
+fn test_parse_imports_regex() {
+    let res = get_import_specifiers(
+        "
+    const a = /import '.\\/x'/;
+    const b = /\\/\\//g.test(url) ? total / count : 0;
+    const d = await /'/.test(s);
+    import { c } from './c';
+    ",
+    );
+    assert_eq!(res, vec!["./c"]);
 }
 
 #[test]
-fn test_tokenize_named_import() {
-    let import_str = "import { myFunc } from './myfile';";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["myFunc", "./myfile"]);
+fn test_parse_import_exact_specifier() {
+    let import = parse_single_import("import { a } from \"./my-dir/file.name\";");
+    assert_eq!(import.specifier, "./my-dir/file.name");
 }
 
 #[test]
-fn test_tokenize_multiple_named_imports() {
-    let import_str = "import { myFunc, myVar } from './myfile';";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["myFunc", "myVar", "./myfile"]);
+fn test_parse_imports_ignores_non_declarations() {
+    let res = get_import_specifiers(
+        "
+    const msg = 'please import the file';
+    const url = import.meta.url;
+    const obj = { import: true, other: foo.import };
+    import { real } from './real';
+    ",
+    );
+    assert_eq!(res, vec!["./real"]);
 }
 
 #[test]
-fn test_tokenize_relative_path_import() {
-    let import_str = "import { myFunc } from '../mydir/myfile';";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["myFunc", "../mydir/myfile"]);
+fn test_parse_imports_non_ascii() {
+    let res = get_import_specifiers("const s = '× ✓ 😀';\nimport { naïve } from './ünïcode';");
+    assert_eq!(res, vec!["./ünïcode"]);
 }
 
 #[test]
-fn test_tokenize_import_with_trailing_semicolon() {
-    let import_str = "import * as myModule from 'mylib';";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["*", "mylib"]);
+fn test_parse_imports_after_import_expression() {
+    let res = get_import_specifiers("const m = import('./m'); import './side';");
+    assert_eq!(res, vec!["./m", "./side"]);
 }
 
 #[test]
-fn test_tokenize_import_with_leading_and_trailing_spaces() {
-    let import_str = "   import { myFunc } from './myfile';   ";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["myFunc", "./myfile"]);
+fn test_parse_imports_jsx() {
+    let res = parse_file(
+        r#"
+    import React from 'react';
+    export const App = () => (
+        <div title="it's">
+            <p>Don't import this from 'here'</p>
+            {items.map((item) => <Item key={item} />)}
+        </div>
+    );
+    import { late } from './late';
+    "#,
+        true,
+    )
+    .imports;
+    let specifiers: Vec<String> = res.into_iter().map(|import| import.specifier).collect();
+    assert_eq!(specifiers, vec!["react", "./late"]);
 }
 
 #[test]
-fn test_tokenize_invalid_import_syntax() {
-    let import_str = "import myFunc from 'mylib';";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["mylib"]);
+fn test_parse_imports_jsx_generic_function_types() {
+    let cases = [
+        "type Fn = <T>(x: T) => T;",
+        "type Props = { onChange: <T>(v: T) => void };",
+        "type Pair = <K, V>(key: K, value: V) => [K, V];",
+        "const id: <T>(x: T) => T = (x) => x;",
+    ];
+
+    for case in cases {
+        let data = format!("{}\nimport {{ a }} from './a';", case);
+        let res: Vec<String> = parse_file(&data, true)
+            .imports
+            .into_iter()
+            .map(|import| import.specifier)
+            .collect();
+        assert_eq!(res, vec!["./a"], "{}", case);
+    }
+
+    let res = parse_file("const el = <T>(x) {x}</T>;\nimport { b } from './b';", true).imports;
+    assert_eq!(res.len(), 1);
 }
 
 #[test]
-fn test_tokenize_whole_module() {
-    let import_str = "import 'mylib';";
-    let tokens = tokenize_import(import_str);
-    assert_eq!(tokens, vec!["mylib"]);
+fn test_parse_imports_span() {
+    let import = parse_single_import("// ×\nconst a = 'é';\n\n  import {\n  b } from './b';");
+    assert_eq!(import.span.line, 4);
+    assert_eq!(import.span.column, 3);
+    assert_eq!(import.span.to_string(), "4:3");
+}
+
+#[test]
+fn test_parse_reexports() {
+    let res = parse_file(
+        "
+    export * from './a';
+    export { x, y as z, default } from './b';
+    export * as ns from './c';
+    export type { T } from './t';
+    export { local };
+    export const value = 1;
+    export default function () {}
+    ",
+        false,
+    )
+    .imports;
+    assert_eq!(res.len(), 4);
+    assert!(res
+        .iter()
+        .all(|import| import.kind == TsImportKind::ReExport));
+
+    assert!(res[0].export_all);
+    assert_eq!(res[0].specifier, "./a");
+
+    let names: Vec<(&str, &str)> = res[1]
+        .named
+        .iter()
+        .map(|n| (n.imported.as_str(), n.local.as_str()))
+        .collect();
+    assert_eq!(names, vec![("x", "x"), ("y", "z"), ("default", "default")]);
+
+    assert_eq!(res[2].namespace_binding.as_deref(), Some("ns"));
+    assert!(!res[2].export_all);
+
+    assert!(res[3].type_only);
+    assert_eq!(res[3].specifier, "./t");
+}
+
+#[test]
+fn test_parse_dynamic_imports() {
+    let parsed = parse_file(
+        "
+    const Home = lazy(() => import('./pages/Home'));
+    const m = await import(`./pages/About`, { with: { type: 'json' } });
+    const page = await import(`./pages/${name}`);
+    const other = import(getPath('./x'));
+    const joined = import('./a' + suffix);
+    ",
+        false,
+    );
+
+    let dynamic: Vec<(&str, TsImportKind)> = parsed
+        .imports
+        .iter()
+        .map(|import| (import.specifier.as_str(), import.kind))
+        .collect();
+    assert_eq!(
+        dynamic,
+        vec![
+            ("./pages/Home", TsImportKind::Dynamic),
+            ("./pages/About", TsImportKind::Dynamic),
+        ]
+    );
+    assert_eq!(parsed.imports[0].span.to_string(), "2:29");
+
+    let unresolvable: Vec<(&str, String)> = parsed
+        .unresolvable_imports
+        .iter()
+        .map(|import| (import.expression.as_str(), import.span.to_string()))
+        .collect();
+    assert_eq!(
+        unresolvable,
+        vec![
+            ("import(`./pages/${name}`)", "4:24".to_string()),
+            ("import(getPath('./x'))", "5:19".to_string()),
+            ("import('./a' + suffix)", "6:20".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_commonjs() {
+    let parsed = parse_file(
+        "
+    const fs = require('fs');
+    const { join } = require(`path`);
+    import util = require('./util');
+    import type Types = require('./types');
+    import Alias = Some.Namespace;
+    const plugin = require(pluginPath);
+    const resolved = require.resolve('./not-a-dependency');
+    module.exports = { join };
+    ",
+        false,
+    );
+
+    let requires: Vec<(&str, Option<&str>, bool)> = parsed
+        .imports
+        .iter()
+        .map(|import| {
+            assert_eq!(import.kind, TsImportKind::Require);
+            assert_eq!(import.module_system(), ModuleSystem::CommonJs);
+            (
+                import.specifier.as_str(),
+                import.default_binding.as_deref(),
+                import.type_only,
+            )
+        })
+        .collect();
+    assert_eq!(
+        requires,
+        vec![
+            ("fs", None, false),
+            ("path", None, false),
+            ("./util", Some("util"), false),
+            ("./types", Some("Types"), true),
+        ]
+    );
+    assert_eq!(parsed.imports[2].span.to_string(), "4:5");
+    assert_eq!(
+        parsed.unresolvable_imports[0].expression,
+        "require(pluginPath)"
+    );
+    assert!(parsed.commonjs_exports);
+
+    let parsed = parse_file("import a from './a'; export const exports = {};", false);
+    assert_eq!(parsed.imports[0].module_system(), ModuleSystem::Esm);
+    assert!(!parsed.commonjs_exports);
+
+    let parsed = parse_file(
+        "
+    declare function require(id: string): any;
+    function require(id) { return load(id); }
+    class Loader { require(id: string, options?: object) {} }
+    interface NodeRequire { require(id?: string): unknown; }
+    const local = cond ? require(a) : require('./b');
+    ",
+        false,
+    );
+    assert_eq!(parsed.imports.len(), 1);
+    assert_eq!(parsed.imports[0].specifier, "./b");
+    let unresolvable: Vec<&str> = parsed
+        .unresolvable_imports
+        .iter()
+        .map(|import| import.expression.as_str())
+        .collect();
+    assert_eq!(unresolvable, vec!["require(a)"]);
+}
+
+#[test]
+fn test_parse_triple_slash_directives() {
+    let imports = parse_file(
+        r#"
+/// <reference path="./globals.d.ts" />
+/// <reference types='node'/>
+/// <reference lib="es2015" />
+// <reference path="./not-a-directive.d.ts" />
+import a from './a';
+/// <reference path="./after-statement.d.ts" />
+"#,
+        false,
+    )
+    .imports;
+
+    let references: Vec<(TsImportKind, &str, &str, String)> = imports
+        .iter()
+        .map(|import| {
+            (
+                import.kind,
+                import.specifier.as_str(),
+                import.source.as_str(),
+                import.span.to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        references,
+        vec![
+            (
+                TsImportKind::TypeReference,
+                "./globals.d.ts",
+                "./globals.d.ts",
+                "2:1".to_string()
+            ),
+            (
+                TsImportKind::TypeReference,
+                "node",
+                "node",
+                "3:1".to_string()
+            ),
+            (TsImportKind::Static, "./a", "./a", "6:1".to_string()),
+        ]
+    );
+    assert!(matches!(imports[1].import_source, TsImportSource::Package));
+}
+
+#[test]
+fn test_parse_import_attributes() {
+    let imports = parse_file(
+        "
+    import data from './data.json' with { type: 'json' };
+    import styles from './styles.css' assert { type: 'css', 'resolution-mode': 'import' };
+    export { default as config } from './config.json' with { type: 'json' };
+    const lazyData = await import('./lazy.json', { with: { type: 'json' } });
+    import { with as w } from './keyword';
+    ",
+        false,
+    )
+    .imports;
+
+    let attributes: Vec<(&str, &str, Option<&str>)> = imports
+        .iter()
+        .map(|import| {
+            (
+                import.specifier.as_str(),
+                import.source.as_str(),
+                import.attribute_type(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        attributes,
+        vec![
+            ("./data.json", "./data.json", Some("json")),
+            ("./styles.css", "./styles.css", Some("css")),
+            ("./config.json", "./config.json", Some("json")),
+            ("./lazy.json", "./lazy.json", Some("json")),
+            ("./keyword", "./keyword", None),
+        ]
+    );
+    assert_eq!(
+        imports[1].attributes[1],
+        ImportAttribute {
+            key: "resolution-mode".to_string(),
+            value: "import".to_string(),
+        }
+    );
+    assert_eq!(imports[0].span.end, 57);
+}
+
+#[test]
+fn test_imported_names() {
+    let imports = parse_file(
+        "
+    import React, { useState as useLocalState } from 'react';
+    import * as path from 'path';
+    export { default as Button, type Props } from './Button';
+    export * from './theme';
+    import './styles';
+    ",
+        false,
+    )
+    .imports;
+
+    let names: Vec<Vec<(&str, &str)>> = imports
+        .iter()
+        .map(|import| import.imported_names())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            vec![("default", "React"), ("useState", "useLocalState")],
+            vec![("*", "path")],
+            vec![("default", "Button"), ("Props", "Props")],
+            vec![("*", "*")],
+            vec![],
+        ]
+    );
+}
+
+#[test]
+fn test_parse_exports() {
+    let exports = parse_file(
+        "
+    export const a = 1, a2 = { x: [1, 2] }, a3: Map<string, number> = new Map();
+    export const a4 = (x, y) => x, a6: Map<string, Array<number>> = new Map(), a7 = 1
+    export const a8: Map<string, Map<string, Array<number>>> = new Map(), a9: Array<Set<T>>= [], a10 = 2
+    export const a5 = f(1, 2)
+    export const a11 = 1
+    fake(), notExported
+    export const a12 = compute()
+    , a13 = a12
+        instanceof Object
+    export let { b, c: d, e = f, ...g } = obj;
+    export const [h, [i]] = list;
+    export async function j() {}
+    export function* k() {}
+    export abstract class L {}
+    export interface M {}
+    export type N = string;
+    export type { O, P as Q };
+    export const enum R {}
+    export declare namespace S {}
+    export { t, u as v };
+    export { reexported } from './other';
+    export default class {}
+    export = W;
+    ",
+        false,
+    )
+    .exports;
+
+    let names: Vec<(&str, TsExportKind)> = exports
+        .iter()
+        .map(|export| (export.name.as_str(), export.kind))
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("a", TsExportKind::Variable),
+            ("a2", TsExportKind::Variable),
+            ("a3", TsExportKind::Variable),
+            ("a4", TsExportKind::Variable),
+            ("a6", TsExportKind::Variable),
+            ("a7", TsExportKind::Variable),
+            ("a8", TsExportKind::Variable),
+            ("a9", TsExportKind::Variable),
+            ("a10", TsExportKind::Variable),
+            ("a5", TsExportKind::Variable),
+            ("a11", TsExportKind::Variable),
+            ("a12", TsExportKind::Variable),
+            ("a13", TsExportKind::Variable),
+            ("b", TsExportKind::Variable),
+            ("d", TsExportKind::Variable),
+            ("e", TsExportKind::Variable),
+            ("g", TsExportKind::Variable),
+            ("h", TsExportKind::Variable),
+            ("i", TsExportKind::Variable),
+            ("j", TsExportKind::Function),
+            ("k", TsExportKind::Function),
+            ("L", TsExportKind::Class),
+            ("M", TsExportKind::Interface),
+            ("N", TsExportKind::TypeAlias),
+            ("O", TsExportKind::Named),
+            ("Q", TsExportKind::Named),
+            ("R", TsExportKind::Enum),
+            ("S", TsExportKind::Namespace),
+            ("t", TsExportKind::Named),
+            ("v", TsExportKind::Named),
+            ("default", TsExportKind::Default),
+            ("default", TsExportKind::Assignment),
+        ]
+    );
+    assert_eq!(exports[19].span.to_string(), "13:5");
+}
+
+#[test]
+fn test_parse_export_docs() {
+    let exports = parse_file(
+        "
+    /**
+     * Entry point of the plugin.
+     * @public
+     */
+    export function activate() {}
+
+    /** Not attached */
+    const internal = 1;
+    export const other = internal;
+    ",
+        false,
+    )
+    .exports;
+
+    assert_eq!(
+        exports[0].doc.as_deref(),
+        Some("Entry point of the plugin.\n@public")
+    );
+    assert!(exports[0].is_public_api());
+    assert_eq!(exports[1].doc, None);
+    assert!(!exports[1].is_public_api());
+}
+
+#[test]
+fn test_is_public_api() {
+    let cases = [
+        ("/** @public */ export const a = 1;", true),
+        (
+            "/**\n * Shared helper.\n * @public\n */ export function b() {}",
+            true,
+        ),
+        ("/** @publicApi */ export const c = 1;", false),
+        ("/** Not @public here */ export const d = 1;", false),
+        ("/** @internal */ export const e = 1;", false),
+    ];
+
+    for (data, expected) in cases {
+        let exports = parse_file(data, false).exports;
+        assert_eq!(exports[0].is_public_api(), expected, "{}", data);
+    }
 }
@@ -0,0 +1,814 @@
+use crate::json::JsonValue;
+use crate::packages::{
+    find_package_json, read_package_json, split_package_specifier, types_package, PackageJson,
+    DEFAULT_CONDITIONS,
+};
+use crate::structs::{ModuleSystem, TsFile, TsImport, TsImportKind, TsImportSource};
+use crate::tsconfig::TsConfig;
+use path_absolutize::*;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/**
+ * Extensions of the files that are parsed, in the order TypeScript tries them when
+ * a specifier has no extension. `.d.ts` files have the `ts` extension.
+ */
+pub static SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "js", "jsx", "mjs", "cjs"];
+
+/**
+ * Extensions appended to an extensionless specifier, or to `index` in a directory,
+ * in order.
+ */
+static RESOLVED_EXTENSIONS: &[&str] = &[
+    ".ts", ".tsx", ".d.ts", ".mts", ".d.mts", ".cts", ".d.cts", ".js", ".jsx", ".mjs", ".cjs",
+];
+
+/**
+ * TypeScript sources compile to JavaScript next to them, so a specifier naming the
+ * emitted file, `./x.js`, refers to the `./x.ts` source.
+ */
+static EMITTED_EXTENSIONS: &[(&str, &[&str])] = &[
+    (".js", &[".ts", ".tsx", ".d.ts"]),
+    (".jsx", &[".tsx"]),
+    (".mjs", &[".mts", ".d.mts"]),
+    (".cjs", &[".cts", ".d.cts"]),
+];
+
+pub fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+}
+
+/**
+ * Resolves imports to the files they refer to, with the `baseUrl` and `paths`
+ * aliases of the project's `tsconfig.json` and the `exports` and `imports` maps of
+ * its `package.json` files.
+ */
+#[derive(Debug)]
+pub struct Resolver {
+    tsconfig: TsConfig,
+    // conditions matched in `exports` and `imports` maps
+    conditions: Vec<String>,
+    // packages of the workspace, which bare specifiers naming them resolve into
+    workspace_packages: Vec<PackageJson>,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Resolver::new(TsConfig::default())
+    }
+}
+
+impl Resolver {
+    pub fn new(tsconfig: TsConfig) -> Self {
+        Resolver {
+            tsconfig,
+            conditions: DEFAULT_CONDITIONS.iter().map(|c| c.to_string()).collect(),
+            workspace_packages: Vec::new(),
+        }
+    }
+
+    /**
+     * Adds `conditions` to the default ones, which stay matched so that `types`,
+     * `node` and the module system of each import still select their targets.
+     */
+    pub fn with_conditions(mut self, conditions: Vec<String>) -> Self {
+        for condition in conditions {
+            if !self.conditions.contains(&condition) {
+                self.conditions.push(condition);
+            }
+        }
+        self
+    }
+
+    pub fn with_workspace_packages(mut self, packages: Vec<PackageJson>) -> Self {
+        self.workspace_packages = packages;
+        self
+    }
+
+    /**
+     * Returns the absolute path of the project file `import` refers to, or None for
+     * installed packages and for modules that are not on disk.
+     */
+    pub fn resolve_import(&self, file: &TsFile, import: &TsImport) -> Option<PathBuf> {
+        let path = self
+            .import_candidates(file, import)?
+            .into_iter()
+            .find(|candidate| candidate.is_file())?;
+        // workspace packages are linked into `node_modules`
+        let path = fs::canonicalize(path).ok()?;
+
+        let installed = path
+            .components()
+            .any(|component| component == Component::Normal("node_modules".as_ref()));
+        (!installed).then_some(path)
+    }
+
+    /**
+     * Returns the absolute paths tried, in order, to resolve `import`, or None for
+     * packages that neither a tsconfig alias nor a `package.json` resolves.
+     */
+    pub fn import_candidates(&self, file: &TsFile, import: &TsImport) -> Option<Vec<PathBuf>> {
+        if matches!(import.import_source, TsImportSource::Package) {
+            if import.kind == TsImportKind::TypeReference {
+                let dir = Path::new(&file.relative_path).parent()?;
+                return Some(self.type_reference_candidates(dir, &import.source));
+            }
+
+            let mut candidates: Vec<PathBuf> = self
+                .tsconfig
+                .alias_paths(&import.source)
+                .iter()
+                .flat_map(|module_path| module_candidates(module_path))
+                .collect();
+            candidates.extend(self.package_candidates(file, import));
+            return (!candidates.is_empty()).then_some(candidates);
+        }
+
+        let dir = Path::new(&file.relative_path).parent()?;
+        let module_path = dir.join(&import.source);
+        let module_path = module_path.absolutize().ok()?;
+
+        // `/// <reference path="..." />` names the file exactly
+        if import.kind == TsImportKind::TypeReference {
+            return Some(vec![module_path.to_path_buf()]);
+        }
+
+        Some(module_candidates(&module_path))
+    }
+
+    /**
+     * Returns the files a bare or `#internal` specifier can refer to through the
+     * `imports` map of the closest `package.json`, or through the package it names:
+     * the project's own package, a package of the workspace, or one linked into
+     * `node_modules`.
+     */
+    fn package_candidates(&self, file: &TsFile, import: &TsImport) -> Vec<PathBuf> {
+        let Some(dir) = Path::new(&file.relative_path).parent() else {
+            return Vec::new();
+        };
+        let conditions = self.active_conditions(import);
+
+        if import.source.starts_with('#') {
+            return find_package_json(dir)
+                .and_then(|package| {
+                    let target = package.resolve_imports(&import.source, &conditions)?;
+                    target_candidates(&package, &target)
+                })
+                .unwrap_or_default();
+        }
+
+        let (name, subpath) = split_package_specifier(&import.source);
+        let own_package = find_package_json(dir).filter(|package| package.name() == Some(name));
+        let workspace_package = self
+            .workspace_packages
+            .iter()
+            .find(|package| package.name() == Some(name));
+        let installed_packages = dir
+            .ancestors()
+            .filter_map(|ancestor| read_package_json(&ancestor.join("node_modules").join(name)));
+
+        let mut candidates: Vec<PathBuf> = own_package
+            .iter()
+            .chain(workspace_package)
+            .flat_map(|package| entry_candidates(package, &subpath, &conditions))
+            .collect();
+        // entry points of workspace packages often name their build output, which
+        // is compiled from `src`
+        if let Some(package) = workspace_package {
+            let src = package.dir.join("src");
+            match subpath.as_str() {
+                "." => candidates.extend(index_candidates(&src)),
+                _ => candidates.extend(module_candidates(&src.join(&subpath))),
+            }
+        }
+        candidates.extend(
+            installed_packages
+                .flat_map(|package| entry_candidates(&package, &subpath, &conditions)),
+        );
+
+        candidates
+    }
+
+    /**
+     * Returns the declarations `/// <reference types="name" />` in `dir` can refer
+     * to: the `name` package of each `typeRoots` directory, or `@types/name` in the
+     * `node_modules` above `dir` when the tsconfig sets no `typeRoots`, then the
+     * installed package itself.
+     */
+    fn type_reference_candidates(&self, dir: &Path, name: &str) -> Vec<PathBuf> {
+        let mut candidates: Vec<PathBuf> = match &self.tsconfig.type_roots {
+            Some(type_roots) => type_roots
+                .iter()
+                .flat_map(|type_root| directory_candidates(&type_root.join(name)))
+                .collect(),
+            None => dir
+                .ancestors()
+                .flat_map(|ancestor| {
+                    directory_candidates(&ancestor.join("node_modules").join(types_package(name)))
+                })
+                .collect(),
+        };
+        candidates.extend(
+            dir.ancestors().flat_map(|ancestor| {
+                directory_candidates(&ancestor.join("node_modules").join(name))
+            }),
+        );
+
+        candidates
+    }
+
+    /**
+     * `import` and `require` conditions only match imports of their own module
+     * system.
+     */
+    fn active_conditions(&self, import: &TsImport) -> Vec<&str> {
+        let excluded = match import.module_system() {
+            ModuleSystem::Esm => "require",
+            ModuleSystem::CommonJs => "import",
+        };
+
+        self.conditions
+            .iter()
+            .map(String::as_str)
+            .filter(|condition| *condition != excluded)
+            .collect()
+    }
+
+    /**
+     * Records the project file each import of `files` refers to in its `resolved`
+     * path, leaving the parsed import as written. Package imports resolve to a file
+     * through a tsconfig alias such as `@app/models/User`, a `package.json` such as
+     * `#internal/db` or a workspace package.
+     */
+    pub fn resolve_imports(&self, files: &mut [TsFile]) {
+        for file in files.iter_mut() {
+            let resolved: Vec<Option<PathBuf>> = file
+                .imports
+                .iter()
+                .map(|import| self.resolve_import(file, import))
+                .collect();
+
+            for (import, resolved) in file.imports.iter_mut().zip(resolved) {
+                import.resolved = resolved;
+            }
+        }
+    }
+}
+
+/**
+ * Package.json fields naming the entry point of a folder, in the order TypeScript
+ * reads them.
+ */
+static PACKAGE_ENTRY_FIELDS: &[&str] = &["types", "typings", "main"];
+
+/**
+ * Returns the files a module path can refer to, in TypeScript's order: the path as
+ * a file, then as a directory through its `package.json` and its `index` file.
+ */
+fn module_candidates(module_path: &Path) -> Vec<PathBuf> {
+    let mut candidates = file_candidates(module_path);
+    candidates.extend(directory_candidates(module_path));
+
+    candidates
+}
+
+/**
+ * Returns the TypeScript source of an emitted JavaScript file, the path itself,
+ * then the path with each extension appended.
+ */
+fn file_candidates(module_path: &Path) -> Vec<PathBuf> {
+    let path = module_path.to_string_lossy();
+    let mut candidates = Vec::new();
+
+    for (emitted, sources) in EMITTED_EXTENSIONS {
+        if let Some(stem) = path.strip_suffix(emitted) {
+            candidates.extend(
+                sources
+                    .iter()
+                    .map(|ext| PathBuf::from(format!("{stem}{ext}"))),
+            );
+        }
+    }
+
+    candidates.push(module_path.to_path_buf());
+    candidates.extend(
+        RESOLVED_EXTENSIONS
+            .iter()
+            .map(|ext| PathBuf::from(format!("{path}{ext}"))),
+    );
+
+    candidates
+}
+
+/**
+ * Returns the entry points named by the `types`, `typings` and `main` fields of
+ * the `package.json` in `dir`, if any, then its `index` file.
+ */
+fn directory_candidates(dir: &Path) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+
+    if let Some(package) = read_package_json(dir) {
+        for field in PACKAGE_ENTRY_FIELDS {
+            if let Some(entry) = package.json.get(field).and_then(JsonValue::as_str) {
+                let Ok(entry) = dir.join(entry).absolutize().map(|path| path.to_path_buf()) else {
+                    continue;
+                };
+                candidates.extend(file_candidates(&entry));
+                candidates.extend(index_candidates(&entry));
+            }
+        }
+    }
+
+    candidates.extend(index_candidates(dir));
+    candidates
+}
+
+/**
+ * Returns the files `subpath` of `package` can refer to: its target in the
+ * `exports` map, or without one the entry points of the package directory or the
+ * module at `subpath`.
+ */
+fn entry_candidates(package: &PackageJson, subpath: &str, conditions: &[&str]) -> Vec<PathBuf> {
+    match package.json.get("exports") {
+        Some(_) => package
+            .resolve_exports(subpath, conditions)
+            .and_then(|target| target_candidates(package, &target))
+            .unwrap_or_default(),
+        None if subpath == "." => directory_candidates(&package.dir),
+        None => module_candidates(&package.dir.join(subpath)),
+    }
+}
+
+/**
+ * Returns the files an `exports` or `imports` target of `package` can refer to.
+ * Targets that name another package are not followed.
+ */
+fn target_candidates(package: &PackageJson, target: &str) -> Option<Vec<PathBuf>> {
+    if !target.starts_with("./") {
+        return None;
+    }
+    let path = package.dir.join(target).absolutize().ok()?.to_path_buf();
+
+    Some(file_candidates(&path))
+}
+
+fn index_candidates(dir: &Path) -> Vec<PathBuf> {
+    RESOLVED_EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(format!("index{ext}")))
+        .collect()
+}
+
+#[cfg(test)]
+fn create_test_project(name: &str, files: &[&str]) -> crate::fixture::TestDir {
+    let dir = crate::fixture::TestDir::new(name);
+
+    for file in files {
+        let data = if file.ends_with("package.json") {
+            r#"{ "main": "./lib/main.js", "types": "./types/entry" }"#
+        } else {
+            ""
+        };
+        dir.write(file, data);
+    }
+
+    dir
+}
+
+#[cfg(test)]
+fn resolve_test_import(root: &Path, from: &str, data: &str) -> Option<String> {
+    let file = crate::parser::parse_ts_file(
+        data,
+        false,
+        from.to_string(),
+        root.join(from).to_string_lossy().to_string(),
+    );
+    let resolved = Resolver::default().resolve_import(&file, &file.imports[0])?;
+
+    Some(
+        resolved
+            .strip_prefix(root)
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+    )
+}
+
+#[test]
+fn test_resolve_extensions() {
+    let dir = create_test_project(
+        "resolve-extensions",
+        &[
+            "src/app.ts",
+            "src/Button.tsx",
+            "src/legacy.js",
+            "src/esm.mts",
+            "src/module.mjs",
+            "src/common.cts",
+            "src/globals.d.ts",
+            "src/user.service.ts",
+            "src/data.json",
+            "src/both.ts",
+            "src/both.js",
+        ],
+    );
+    let root = dir.path();
+
+    let cases = [
+        ("import { Button } from './Button';", Some("src/Button.tsx")),
+        (
+            "import { Button } from './Button.js';",
+            Some("src/Button.tsx"),
+        ),
+        ("import legacy from './legacy';", Some("src/legacy.js")),
+        ("import legacy from './legacy.js';", Some("src/legacy.js")),
+        ("import esm from './esm.mjs';", Some("src/esm.mts")),
+        ("import esm from './module.mjs';", Some("src/module.mjs")),
+        ("import common from './common.cjs';", Some("src/common.cts")),
+        ("import './globals';", Some("src/globals.d.ts")),
+        (
+            "import { User } from './user.service';",
+            Some("src/user.service.ts"),
+        ),
+        (
+            "import data from './data.json' with { type: 'json' };",
+            Some("src/data.json"),
+        ),
+        ("import both from './both.js';", Some("src/both.ts")),
+        ("import missing from './missing';", None),
+        ("import react from 'react';", None),
+    ];
+
+    for (data, expected) in cases {
+        assert_eq!(
+            resolve_test_import(root, "src/app.ts", data).as_deref(),
+            expected,
+            "{}",
+            data
+        );
+    }
+}
+
+#[test]
+fn test_resolve_directories() {
+    let dir = create_test_project(
+        "resolve-directories",
+        &[
+            "src/app.ts",
+            "src/index.ts",
+            "src/components/index.tsx",
+            "src/components/Button.tsx",
+            "src/utils.ts",
+            "src/utils/index.ts",
+            "src/local-package/package.json",
+            "src/local-package/types/entry.d.ts",
+            "src/local-package/lib/main.js",
+            "src/main-only/package.json",
+            "src/main-only/lib/main.js",
+            "src/js-index/index.js",
+        ],
+    );
+    let root = dir.path();
+    dir.write(
+        "src/main-only/package.json",
+        r#"{ "main": "./lib/main.js" }"#,
+    );
+
+    let cases = [
+        (
+            "import { Button } from './components';",
+            Some("src/components/index.tsx"),
+        ),
+        (
+            "import { Button } from './components/';",
+            Some("src/components/index.tsx"),
+        ),
+        ("import { a } from './utils';", Some("src/utils.ts")),
+        (
+            "import { a } from './utils/index';",
+            Some("src/utils/index.ts"),
+        ),
+        (
+            "import pkg from './local-package';",
+            Some("src/local-package/types/entry.d.ts"),
+        ),
+        (
+            "import pkg from './main-only';",
+            Some("src/main-only/lib/main.js"),
+        ),
+        (
+            "import js from './js-index';",
+            Some("src/js-index/index.js"),
+        ),
+        ("import app from '.';", Some("src/index.ts")),
+        ("import { Button } from '..';", None),
+    ];
+
+    for (data, expected) in cases {
+        assert_eq!(
+            resolve_test_import(root, "src/app.ts", data).as_deref(),
+            expected,
+            "{}",
+            data
+        );
+    }
+    assert_eq!(
+        resolve_test_import(root, "src/components/Button.tsx", "import app from '..';").as_deref(),
+        Some("src/index.ts")
+    );
+}
+
+#[test]
+fn test_resolve_aliases() {
+    let dir = create_test_project(
+        "resolve-aliases",
+        &[
+            "src/app.ts",
+            "src/models/User.ts",
+            "src/utils/file.ts",
+            "src/ui/index.ts",
+        ],
+    );
+    let root = dir.path();
+    dir.write(
+        "tsconfig.json",
+        r#"{ "compilerOptions": { "baseUrl": "src", "paths": { "@app/*": ["*"], "@ui": ["ui"] } } }"#,
+    );
+    let tsconfig = crate::tsconfig::load_tsconfig(&root.join("tsconfig.json")).unwrap();
+    let resolver = Resolver::new(tsconfig);
+
+    let mut files = vec![crate::parser::parse_ts_file(
+        "
+        import { User } from '@app/models/User';
+        import { file } from 'utils/file';
+        import { Button } from '@ui';
+        import react from 'react';
+        ",
+        false,
+        "app.ts".to_string(),
+        root.join("src/app.ts").to_string_lossy().to_string(),
+    )];
+    resolver.resolve_imports(&mut files);
+
+    let resolved: Vec<Option<PathBuf>> = files[0]
+        .imports
+        .iter()
+        .map(|import| import.resolved.clone())
+        .collect();
+    assert_eq!(
+        resolved,
+        vec![
+            Some(root.join("src/models/User.ts")),
+            Some(root.join("src/utils/file.ts")),
+            Some(root.join("src/ui/index.ts")),
+            None,
+        ]
+    );
+    // the imports stay as written
+    assert!(matches!(
+        files[0].imports[0].import_source,
+        TsImportSource::Package
+    ));
+    assert_eq!(files[0].imports[0].source, "@app/models/User");
+}
+
+#[test]
+fn test_resolve_type_references() {
+    let dir = create_test_project(
+        "resolve-type-references",
+        &[
+            "src/app.ts",
+            "types/globals/index.d.ts",
+            "node_modules/@types/node/index.d.ts",
+            "node_modules/typed/index.d.ts",
+        ],
+    );
+    let root = dir.path();
+    let installed = |resolver: &Resolver, data: &str| {
+        let file = crate::parser::parse_ts_file(
+            data,
+            false,
+            "app.ts".to_string(),
+            root.join("src/app.ts").to_string_lossy().to_string(),
+        );
+        resolver
+            .import_candidates(&file, &file.imports[0])
+            .unwrap_or_default()
+            .into_iter()
+            .find(|candidate| candidate.is_file())
+            .map(|path| path.strip_prefix(root).unwrap().to_path_buf())
+    };
+
+    let resolver = Resolver::default();
+    assert_eq!(
+        installed(&resolver, r#"/// <reference types="node" />"#),
+        Some(PathBuf::from("node_modules/@types/node/index.d.ts"))
+    );
+    assert_eq!(
+        installed(&resolver, r#"/// <reference types="typed" />"#),
+        Some(PathBuf::from("node_modules/typed/index.d.ts"))
+    );
+    assert_eq!(
+        resolve_test_import(root, "src/app.ts", r#"/// <reference types="globals" />"#),
+        None
+    );
+
+    // `typeRoots` replaces `node_modules/@types`, and a root within the project
+    // resolves to a project file
+    let resolver = Resolver::new(TsConfig {
+        type_roots: Some(vec![root.join("types")]),
+        ..Default::default()
+    });
+    let file = crate::parser::parse_ts_file(
+        r#"/// <reference types="globals" />"#,
+        false,
+        "app.ts".to_string(),
+        root.join("src/app.ts").to_string_lossy().to_string(),
+    );
+    assert_eq!(
+        resolver.resolve_import(&file, &file.imports[0]),
+        Some(root.join("types/globals/index.d.ts"))
+    );
+    assert_eq!(
+        installed(&resolver, r#"/// <reference types="node" />"#),
+        None
+    );
+}
+
+#[test]
+fn test_resolve_package_json_maps() {
+    let dir = create_test_project(
+        "resolve-package-maps",
+        &[
+            "src/app.ts",
+            "src/config.ts",
+            "src/config.dev.ts",
+            "src/internal/db/client.ts",
+            "src/features/date.ts",
+            "dist/features/date.js",
+            "node_modules/installed/index.js",
+        ],
+    );
+    let root = dir.path();
+    dir.write(
+        "package.json",
+        r##"{
+            "name": "@org/app",
+            "imports": {
+                "#config": { "development": "./src/config.dev.ts", "default": "./src/config.ts" },
+                "#internal/*": "./src/internal/*.js"
+            },
+            "exports": {
+                "./features/*": { "types": "./src/features/*.ts", "default": "./dist/features/*.js" }
+            }
+        }"##,
+    );
+    dir.write(
+        "node_modules/installed/package.json",
+        r#"{ "name": "installed", "main": "index.js" }"#,
+    );
+
+    let cases = [
+        ("import config from '#config';", Some("src/config.ts")),
+        (
+            "import { db } from '#internal/db/client';",
+            Some("src/internal/db/client.ts"),
+        ),
+        (
+            "import { date } from '@org/app/features/date';",
+            Some("src/features/date.ts"),
+        ),
+        ("import { missing } from '#missing';", None),
+        ("import installed from 'installed';", None),
+    ];
+    for (data, expected) in cases {
+        assert_eq!(
+            resolve_test_import(root, "src/app.ts", data).as_deref(),
+            expected,
+            "{}",
+            data
+        );
+    }
+
+    let development = Resolver::default().with_conditions(vec!["development".to_string()]);
+    let file = crate::parser::parse_ts_file(
+        "import config from '#config';",
+        false,
+        "app.ts".to_string(),
+        root.join("src/app.ts").to_string_lossy().to_string(),
+    );
+    assert_eq!(
+        development.resolve_import(&file, &file.imports[0]),
+        Some(root.join("src/config.dev.ts"))
+    );
+
+    // the default conditions stay matched next to the added ones
+    let file = crate::parser::parse_ts_file(
+        "import { date } from '@org/app/features/date';",
+        false,
+        "app.ts".to_string(),
+        root.join("src/app.ts").to_string_lossy().to_string(),
+    );
+    assert_eq!(
+        development.resolve_import(&file, &file.imports[0]),
+        Some(root.join("src/features/date.ts"))
+    );
+}
+
+#[test]
+fn test_resolve_workspace_packages() {
+    let dir = create_test_project(
+        "resolve-workspace",
+        &[
+            "apps/web/src/app.ts",
+            "packages/ui/src/index.ts",
+            "packages/ui/src/Button.tsx",
+            "packages/utils/src/index.ts",
+            "packages/utils/src/date.ts",
+        ],
+    );
+    let root = dir.path();
+    dir.write(
+        "package.json",
+        r#"{ "private": true, "workspaces": ["apps/*", "packages/*"] }"#,
+    );
+    dir.write("apps/web/package.json", r#"{ "name": "web" }"#);
+    dir.write(
+        "packages/ui/package.json",
+        r#"{ "name": "@acme/ui", "main": "./dist/index.js" }"#,
+    );
+    dir.write(
+        "packages/utils/package.json",
+        r#"{ "name": "@acme/utils", "exports": { ".": "./src/index.ts", "./date": "./dist/date.js" } }"#,
+    );
+
+    let packages = crate::workspace::find_workspace_packages(root);
+    let resolver = Resolver::default().with_workspace_packages(packages);
+    let file = crate::parser::parse_ts_file(
+        "
+        import { Button } from '@acme/ui';
+        import { Button } from '@acme/ui/Button';
+        import { format } from '@acme/utils';
+        import { date } from '@acme/utils/date';
+        import react from 'react';
+        ",
+        false,
+        "app.ts".to_string(),
+        root.join("apps/web/src/app.ts")
+            .to_string_lossy()
+            .to_string(),
+    );
+
+    let resolved: Vec<Option<PathBuf>> = file
+        .imports
+        .iter()
+        .map(|import| resolver.resolve_import(&file, import))
+        .collect();
+    assert_eq!(
+        resolved,
+        vec![
+            Some(root.join("packages/ui/src/index.ts")),
+            Some(root.join("packages/ui/src/Button.tsx")),
+            Some(root.join("packages/utils/src/index.ts")),
+            Some(root.join("packages/utils/src/date.ts")),
+            None,
+        ]
+    );
+}
+
+#[test]
+fn test_import_candidates() {
+    let dir = create_test_project("import-candidates", &["src/app.ts"]);
+    let root = dir.path();
+    let file = crate::parser::parse_ts_file(
+        "/// <reference path='./globals.d.ts' />
+        import { a } from './missing.js';
+        import react from 'react';
+        ",
+        false,
+        "app.ts".to_string(),
+        root.join("src/app.ts").to_string_lossy().to_string(),
+    );
+    let resolver = Resolver::default();
+
+    let candidates = resolver.import_candidates(&file, &file.imports[1]).unwrap();
+    let src = root.join("src");
+    assert_eq!(
+        candidates[..5],
+        [
+            src.join("missing.ts"),
+            src.join("missing.tsx"),
+            src.join("missing.d.ts"),
+            src.join("missing.js"),
+            src.join("missing.js.ts"),
+        ]
+    );
+    assert_eq!(candidates.last(), Some(&src.join("missing.js/index.cjs")));
+    assert_eq!(
+        resolver.import_candidates(&file, &file.imports[0]),
+        Some(vec![src.join("globals.d.ts")])
+    );
+    assert_eq!(resolver.import_candidates(&file, &file.imports[2]), None);
+}
@@ -1,36 +1,315 @@
+use crate::lexer::Span;
 use std::fmt;
+use std::path::PathBuf;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub enum TsImportSource {
+    #[default]
     Package,
     Local,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum TsImportKind {
+    // `import ... from 'module'`
+    #[default]
+    Static,
+    // `export ... from 'module'`
+    ReExport,
+    // `import('module')`, loaded lazily at runtime
+    Dynamic,
+    // `require('module')` and `import name = require('module')`
+    Require,
+    // `/// <reference path="..." />` and `/// <reference types="..." />`
+    TypeReference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleSystem {
+    Esm,
+    CommonJs,
+}
+
+/**
+ * What a graph node outside the project stands for.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExternalKind {
+    // a package installed in `node_modules`
+    Package,
+    // a module built into Node.js, such as `fs` or `node:path`
+    Builtin,
+}
+
+/**
+ * The weight of a graph edge: how a file depends on another.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dependency {
+    pub kind: TsImportKind,
+    // the `type` import attribute, e.g. `json`
+    pub attribute_type: Option<String>,
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+        if let Some(attribute_type) = &self.attribute_type {
+            write!(f, " with type {}", attribute_type)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for TsImportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TsImportKind::Static => write!(f, "import"),
+            TsImportKind::ReExport => write!(f, "re-export"),
+            TsImportKind::Dynamic => write!(f, "dynamic import"),
+            TsImportKind::Require => write!(f, "require"),
+            TsImportKind::TypeReference => write!(f, "reference"),
+        }
+    }
+}
+
+/**
+ * A named import, `imported` is the name exported by the module and `local` the
+ * binding it gets in the importing file:
+ *
+ * import { imported as local } from 'module';
+ *
+ * For re-exports `local` is the name the importing file exports it under:
+ *
+ * export { imported as local } from 'module';
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportSpecifier {
+    pub imported: String,
+    pub local: String,
+    // `import { type A } from 'module'`
+    pub is_type: bool,
+}
+
+/**
+ * An import attribute, `type: 'json'` in:
+ *
+ * import data from './data.json' with { type: 'json' };
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportAttribute {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct TsImport {
+    pub kind: TsImportKind,
     pub import_source: TsImportSource,
+    // path of the imported module relative to the importing file, which the
+    // resolver turns into a file
     pub source: String,
+    // absolute path of the project file the import refers to, set by
+    // `Resolver::resolve_imports`
+    pub resolved: Option<PathBuf>,
+    // module specifier as written in the import declaration
+    pub specifier: String,
+    // location of the import declaration in the importing file
+    pub span: Span,
+    // `import name from 'module'`
+    pub default_binding: Option<String>,
+    // `import * as name from 'module'`
+    pub namespace_binding: Option<String>,
+    // `import { a, b as c } from 'module'`
+    pub named: Vec<ImportSpecifier>,
+    // `import 'module'`
+    pub side_effect_only: bool,
+    // `import type { A } from 'module'`
+    pub type_only: bool,
+    // `export * from 'module'`
+    pub export_all: bool,
+    // `with { type: 'json' }`, or the older `assert { type: 'json' }`
+    pub attributes: Vec<ImportAttribute>,
+}
+
+impl TsImport {
+    /**
+     * The names this import takes from the imported module, paired with the names
+     * they are bound to: `default` for a default import, `*` for a namespace import
+     * or `export *`, and the exported name for named specifiers. For re-exports the
+     * bound name is the one exported by the re-exporting file.
+     */
+    pub fn imported_names(&self) -> Vec<(&str, &str)> {
+        let mut names = Vec::new();
+
+        if let Some(binding) = &self.default_binding {
+            names.push(("default", binding.as_str()));
+        }
+        if let Some(binding) = &self.namespace_binding {
+            names.push(("*", binding.as_str()));
+        }
+        if self.export_all {
+            names.push(("*", "*"));
+        }
+        for specifier in &self.named {
+            names.push((specifier.imported.as_str(), specifier.local.as_str()));
+        }
+
+        names
+    }
+
+    /**
+     * Whether the import only takes types, so it is erased from the emitted
+     * JavaScript: `import type { A }`, or `import { type A, type B }`.
+     */
+    pub fn is_type_only(&self) -> bool {
+        self.type_only
+            || (self.default_binding.is_none()
+                && self.namespace_binding.is_none()
+                && !self.named.is_empty()
+                && self.named.iter().all(|specifier| specifier.is_type))
+    }
+
+    /**
+     * The value of the `type` import attribute, e.g. `json` or `css`.
+     */
+    pub fn attribute_type(&self) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == "type")
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    pub fn module_system(&self) -> ModuleSystem {
+        match self.kind {
+            TsImportKind::Require => ModuleSystem::CommonJs,
+            _ => ModuleSystem::Esm,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TsExportKind {
+    // `export const a = 1`
+    Variable,
+    // `export function a() {}`
+    Function,
+    // `export class A {}`
+    Class,
+    // `export interface A {}`
+    Interface,
+    // `export type A = string`
+    TypeAlias,
+    // `export enum A {}`
+    Enum,
+    // `export namespace A {}`
+    Namespace,
+    // `export default a`
+    Default,
+    // `export { a, b as c }`
+    Named,
+    // `export = a`
+    Assignment,
+}
+
+impl fmt::Display for TsExportKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TsExportKind::Variable => write!(f, "variable"),
+            TsExportKind::Function => write!(f, "function"),
+            TsExportKind::Class => write!(f, "class"),
+            TsExportKind::Interface => write!(f, "interface"),
+            TsExportKind::TypeAlias => write!(f, "type"),
+            TsExportKind::Enum => write!(f, "enum"),
+            TsExportKind::Namespace => write!(f, "namespace"),
+            TsExportKind::Default => write!(f, "default export"),
+            TsExportKind::Named => write!(f, "export"),
+            TsExportKind::Assignment => write!(f, "export ="),
+        }
+    }
+}
+
+/**
+ * A name exported by a file itself, as opposed to an `export ... from` re-export
+ * which is kept with the imports. Default exports and `export =` are named
+ * `default`, the name they are imported with.
+ */
+#[derive(Debug, Clone)]
+pub struct TsExport {
+    pub name: String,
+    pub kind: TsExportKind,
+    // location of the `export` keyword
+    pub span: Span,
+    // text of the JSDoc comment before the export
+    pub doc: Option<String>,
+}
+
+impl TsExport {
+    /**
+     * Whether the JSDoc of the export has a `@public` tag, marking it as used
+     * outside of the project.
+     */
+    pub fn is_public_api(&self) -> bool {
+        self.doc.as_ref().is_some_and(|doc| {
+            doc.lines()
+                .any(|line| line.split_whitespace().next() == Some("@public"))
+        })
+    }
+}
+
+/**
+ * An `import()` or `require()` whose module is computed at runtime and so cannot
+ * become an edge:
+ *
+ * import(`./pages/${name}`)
+ */
+#[derive(Debug, Clone)]
+pub struct UnresolvableImport {
+    // source text of the `import()` call
+    pub expression: String,
+    pub span: Span,
 }
 
 #[derive(Debug, Clone)]
 pub struct TsFile {
     pub imports: Vec<TsImport>,
+    pub exports: Vec<TsExport>,
+    pub unresolvable_imports: Vec<UnresolvableImport>,
+    // assigns to `module.exports` or `exports`
+    pub commonjs_exports: bool,
     pub file_name: String,
     pub relative_path: String,
 }
 
+impl TsFile {
+    pub fn uses_commonjs(&self) -> bool {
+        self.commonjs_exports
+            || self
+                .imports
+                .iter()
+                .any(|import| import.module_system() == ModuleSystem::CommonJs)
+    }
+}
+
 impl fmt::Display for TsFile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "File name: {}", self.file_name)?;
         writeln!(f, "Relative path: {}", self.relative_path)?;
         writeln!(f, "Imports:")?;
         for import in &self.imports {
-            write!(f, "    ")?;
+            write!(f, "    {} ", import.kind)?;
             match import.import_source {
                 TsImportSource::Package => write!(f, "from package ")?,
                 TsImportSource::Local => write!(f, "from local file ")?,
             }
-            writeln!(f, "{}", import.source)?;
+            if import.type_only {
+                write!(f, "types ")?;
+            }
+            write!(f, "{}", import.source)?;
+            if let Some(attribute_type) = import.attribute_type() {
+                write!(f, " with type {}", attribute_type)?;
+            }
+            writeln!(f, " ({})", import.span)?;
         }
         Ok(())
     }
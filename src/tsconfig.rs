@@ -0,0 +1,269 @@
+use crate::json::{parse_json, JsonValue};
+use path_absolutize::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/**
+ * The module resolution options of a `tsconfig.json`, merged with the configs it
+ * extends.
+ */
+#[derive(Debug, Default, Clone)]
+pub struct TsConfig {
+    // absolute `compilerOptions.baseUrl`
+    pub base_url: Option<PathBuf>,
+    // `compilerOptions.paths` patterns with their substitutions, in order
+    pub paths: Vec<(String, Vec<String>)>,
+    // directory of the config declaring `paths`, which substitutions are relative
+    // to when there is no `baseUrl`
+    pub paths_dir: Option<PathBuf>,
+    // absolute `compilerOptions.typeRoots`, the directories holding the packages
+    // named by `/// <reference types="..." />`
+    pub type_roots: Option<Vec<PathBuf>>,
+}
+
+/**
+ * Returns the `tsconfig.json` in `dir` or in its closest parent directory.
+ */
+pub fn find_tsconfig(dir: &Path) -> Option<PathBuf> {
+    let dir = dir.absolutize().ok()?;
+
+    dir.ancestors()
+        .map(|ancestor| ancestor.join("tsconfig.json"))
+        .find(|config| config.is_file())
+}
+
+/**
+ * Reads the config at `path` and the chain of configs it extends. Returns None
+ * when a config cannot be read or parsed.
+ */
+pub fn load_tsconfig(path: &Path) -> Option<TsConfig> {
+    load_tsconfig_chain(path, &mut Vec::new())
+}
+
+fn load_tsconfig_chain(path: &Path, visited: &mut Vec<PathBuf>) -> Option<TsConfig> {
+    let path = path.absolutize().ok()?.to_path_buf();
+    // a config extending itself, directly or not, stops here
+    if visited.contains(&path) {
+        return Some(TsConfig::default());
+    }
+    visited.push(path.clone());
+
+    let json = parse_json(&fs::read_to_string(&path).ok()?)?;
+    let dir = path.parent()?;
+
+    // `extends` is a path or, since TypeScript 5.0, a list of paths applied in order
+    let mut config = TsConfig::default();
+    let extends: Vec<&str> = match json.get("extends") {
+        Some(JsonValue::String(extends)) => vec![extends.as_str()],
+        Some(JsonValue::Array(extends)) => extends.iter().filter_map(JsonValue::as_str).collect(),
+        _ => Vec::new(),
+    };
+    for extends in extends {
+        let parent = resolve_extends(dir, extends)
+            .and_then(|parent| load_tsconfig_chain(&parent, visited))?;
+        config.merge(parent);
+    }
+
+    let Some(options) = json.get("compilerOptions") else {
+        return Some(config);
+    };
+
+    if let Some(base_url) = options.get("baseUrl").and_then(JsonValue::as_str) {
+        config.base_url = Some(dir.join(base_url).absolutize().ok()?.to_path_buf());
+    }
+    if let Some(paths) = options.get("paths").and_then(JsonValue::as_object) {
+        config.paths = paths
+            .iter()
+            .map(|(pattern, substitutions)| {
+                let substitutions = substitutions
+                    .as_array()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .map(String::from)
+                    .collect();
+                (pattern.clone(), substitutions)
+            })
+            .collect();
+        config.paths_dir = Some(dir.to_path_buf());
+    }
+    if let Some(type_roots) = options.get("typeRoots").and_then(JsonValue::as_array) {
+        config.type_roots = Some(
+            type_roots
+                .iter()
+                .filter_map(JsonValue::as_str)
+                .filter_map(|root| Some(dir.join(root).absolutize().ok()?.to_path_buf()))
+                .collect(),
+        );
+    }
+
+    Some(config)
+}
+
+/**
+ * Returns the config named by an `extends` in the config directory `dir`: a path,
+ * with or without its `.json` extension, or a config in an installed package such
+ * as `@tsconfig/node18/tsconfig.json`.
+ */
+fn resolve_extends(dir: &Path, extends: &str) -> Option<PathBuf> {
+    let with_json = |path: PathBuf| -> Vec<PathBuf> {
+        vec![
+            path.clone(),
+            PathBuf::from(format!("{}.json", path.display())),
+            path.join("tsconfig.json"),
+        ]
+    };
+
+    let candidates = if extends.starts_with('.') || Path::new(extends).is_absolute() {
+        with_json(dir.join(extends))
+    } else {
+        dir.ancestors()
+            .flat_map(|ancestor| with_json(ancestor.join("node_modules").join(extends)))
+            .collect()
+    };
+
+    candidates.into_iter().find(|candidate| candidate.is_file())
+}
+
+impl TsConfig {
+    /**
+     * Options of an extending config override those of the config it extends.
+     */
+    fn merge(&mut self, parent: TsConfig) {
+        if parent.base_url.is_some() {
+            self.base_url = parent.base_url;
+        }
+        if parent.paths_dir.is_some() {
+            self.paths = parent.paths;
+            self.paths_dir = parent.paths_dir;
+        }
+        if parent.type_roots.is_some() {
+            self.type_roots = parent.type_roots;
+        }
+    }
+
+    /**
+     * Returns the module paths a non-relative specifier can refer to: the
+     * substitutions of the `paths` pattern that matches it, then the specifier
+     * relative to `baseUrl`. An exact pattern wins over a pattern with a `*`, and
+     * among those the pattern with the longest prefix wins.
+     */
+    pub fn alias_paths(&self, specifier: &str) -> Vec<PathBuf> {
+        let mut module_paths = Vec::new();
+
+        let paths_base = self.base_url.as_ref().or(self.paths_dir.as_ref());
+        if let Some(paths_base) = paths_base {
+            if let Some((substitutions, matched)) = self.match_paths(specifier) {
+                module_paths.extend(
+                    substitutions
+                        .iter()
+                        .map(|substitution| paths_base.join(substitution.replace('*', matched))),
+                );
+            }
+        }
+
+        if let Some(base_url) = &self.base_url {
+            module_paths.push(base_url.join(specifier));
+        }
+
+        module_paths
+    }
+
+    /**
+     * Returns the substitutions of the `paths` pattern matching `specifier`, with
+     * the text matched by its `*`.
+     */
+    fn match_paths<'a>(&self, specifier: &'a str) -> Option<(&Vec<String>, &'a str)> {
+        if let Some((_, substitutions)) =
+            self.paths.iter().find(|(pattern, _)| pattern == specifier)
+        {
+            return Some((substitutions, ""));
+        }
+
+        self.paths
+            .iter()
+            .filter_map(|(pattern, substitutions)| {
+                let (prefix, suffix) = pattern.split_once('*')?;
+                let matched = specifier.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                Some((prefix.len(), substitutions, matched))
+            })
+            .max_by_key(|(prefix_len, _, _)| *prefix_len)
+            .map(|(_, substitutions, matched)| (substitutions, matched))
+    }
+}
+
+#[test]
+fn test_load_tsconfig() {
+    let dir = crate::fixture::TestDir::new("tsconfig");
+    let root = dir.path();
+    fs::create_dir_all(root.join("app/src")).unwrap();
+
+    dir.write(
+        "node_modules/@tsconfig/base/tsconfig.json",
+        r#"{ "compilerOptions": { "strict": true, "baseUrl": "." } }"#,
+    );
+    dir.write(
+        "tsconfig.base.json",
+        r#"{
+            // shared by every app
+            "extends": "@tsconfig/base",
+            "compilerOptions": {
+                "paths": {
+                    "@shared/*": ["shared/*", "fallback/*"],
+                    "@shared/config": ["config/index.ts"],
+                },
+                "typeRoots": ["./types", "./node_modules/@types"],
+            },
+        }"#,
+    );
+    dir.write(
+        "app/tsconfig.json",
+        r#"{
+            "extends": "../tsconfig.base",
+            "compilerOptions": {
+                "baseUrl": "./src",
+                "paths": {
+                    "@app/*": ["*"],
+                    "@app/components/*": ["ui/components/*"]
+                }
+            }
+        }"#,
+    );
+
+    assert_eq!(
+        find_tsconfig(&root.join("app/src")),
+        Some(root.join("app/tsconfig.json"))
+    );
+
+    let base = load_tsconfig(&root.join("tsconfig.base.json")).unwrap();
+    assert_eq!(
+        base.base_url.as_deref(),
+        Some(root.join("node_modules/@tsconfig/base").as_path())
+    );
+    assert_eq!(
+        base.alias_paths("@shared/config"),
+        vec![
+            root.join("node_modules/@tsconfig/base/config/index.ts"),
+            root.join("node_modules/@tsconfig/base/@shared/config"),
+        ]
+    );
+
+    let app = load_tsconfig(&root.join("app/tsconfig.json")).unwrap();
+    let src = root.join("app/src");
+    assert_eq!(
+        app.alias_paths("@app/components/Button"),
+        vec![
+            src.join("ui/components/Button"),
+            src.join("@app/components/Button")
+        ]
+    );
+    assert_eq!(
+        app.alias_paths("@app/models/User"),
+        vec![src.join("models/User"), src.join("@app/models/User")]
+    );
+    assert_eq!(app.alias_paths("utils/file"), vec![src.join("utils/file")]);
+    assert_eq!(
+        app.type_roots,
+        Some(vec![root.join("types"), root.join("node_modules/@types")])
+    );
+}
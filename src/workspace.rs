@@ -0,0 +1,244 @@
+use crate::json::JsonValue;
+use crate::packages::{read_package_json, PackageJson};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/**
+ * Returns the packages of the workspace rooted at `root`, listed by the
+ * `workspaces` of its `package.json` (npm, yarn) or by its `pnpm-workspace.yaml`.
+ */
+pub fn find_workspace_packages(root: &Path) -> Vec<PackageJson> {
+    let Ok(root) = fs::canonicalize(root) else {
+        return Vec::new();
+    };
+
+    let mut patterns = Vec::new();
+    if let Some(package) = read_package_json(&root) {
+        // `"workspaces": [...]`, or yarn's `"workspaces": { "packages": [...] }`
+        let workspaces = package.json.get("workspaces");
+        let workspaces = workspaces
+            .and_then(|workspaces| workspaces.get("packages"))
+            .or(workspaces);
+        if let Some(workspaces) = workspaces.and_then(JsonValue::as_array) {
+            patterns.extend(
+                workspaces
+                    .iter()
+                    .filter_map(JsonValue::as_str)
+                    .map(String::from),
+            );
+        }
+    }
+    if let Ok(data) = fs::read_to_string(root.join("pnpm-workspace.yaml")) {
+        patterns.extend(parse_pnpm_workspace(&data));
+    }
+
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    for pattern in patterns.iter().filter(|pattern| !pattern.starts_with('!')) {
+        for dir in expand_pattern(&root, pattern) {
+            if !dirs.contains(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+    for pattern in patterns
+        .iter()
+        .filter_map(|pattern| pattern.strip_prefix('!'))
+    {
+        let excluded = expand_pattern(&root, pattern);
+        dirs.retain(|dir| !excluded.contains(dir));
+    }
+
+    dirs.sort();
+    dirs.iter()
+        .filter_map(|dir| read_package_json(dir))
+        .filter(|package| package.name().is_some())
+        .collect()
+}
+
+/**
+ * Returns the workspace package containing `path`, the one with the deepest
+ * directory when packages are nested.
+ */
+pub fn package_of<'a>(packages: &'a [PackageJson], path: &Path) -> Option<&'a PackageJson> {
+    packages
+        .iter()
+        .filter(|package| path.starts_with(&package.dir))
+        .max_by_key(|package| package.dir.components().count())
+}
+
+/**
+ * Reads the globs of the `packages` list of a `pnpm-workspace.yaml`, written
+ * either as a block sequence with one `- 'glob'` item per line or as a flow
+ * sequence such as `packages: ['apps', 'tools']`. Items may be quoted or not.
+ */
+fn parse_pnpm_workspace(data: &str) -> Vec<String> {
+    let unquote = |value: &str| {
+        value
+            .trim()
+            .trim_matches(|c| c == '\'' || c == '"')
+            .to_string()
+    };
+
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in data.lines() {
+        let line = line.split(" #").next().unwrap_or(line);
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        if let Some(value) = line.strip_prefix("packages:") {
+            let value = value.trim();
+            if let Some(list) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                patterns.extend(list.split(',').map(unquote).filter(|p| !p.is_empty()));
+            }
+            in_packages = value.is_empty();
+        } else if in_packages {
+            match line.trim_start().strip_prefix('-') {
+                Some(item) if line.starts_with(char::is_whitespace) || line.starts_with('-') => {
+                    patterns.push(unquote(item))
+                }
+                _ => in_packages = false,
+            }
+        }
+    }
+
+    patterns
+}
+
+/**
+ * Returns the directories matching a workspace glob relative to `root`, where `*`
+ * matches one directory name and `**` any number of nested directories.
+ */
+fn expand_pattern(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let mut dirs = vec![root.to_path_buf()];
+
+    for segment in pattern.split('/').filter(|segment| !segment.is_empty()) {
+        dirs = dirs
+            .iter()
+            .flat_map(|dir| match segment {
+                "**" => descendant_dirs(dir),
+                _ if segment.contains('*') => child_dirs(dir)
+                    .into_iter()
+                    .filter(|child| {
+                        child
+                            .file_name()
+                            .and_then(|name| name.to_str())
+                            .is_some_and(|name| matches_wildcard(segment, name))
+                    })
+                    .collect(),
+                _ => vec![dir.join(segment)],
+            })
+            .filter(|dir| dir.is_dir())
+            .collect();
+    }
+
+    dirs
+}
+
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    match pattern.split_once('*') {
+        Some((prefix, suffix)) => {
+            name.len() >= prefix.len() + suffix.len()
+                && name.starts_with(prefix)
+                && name.ends_with(suffix)
+        }
+        None => pattern == name,
+    }
+}
+
+/**
+ * Returns the directories in `dir`, without `node_modules` and hidden
+ * directories.
+ */
+fn child_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name != "node_modules" && !name.starts_with('.'))
+        })
+        .collect()
+}
+
+fn descendant_dirs(dir: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![dir.to_path_buf()];
+    let mut index = 0;
+
+    while index < dirs.len() {
+        let children = child_dirs(&dirs[index]);
+        dirs.extend(children);
+        index += 1;
+    }
+
+    dirs
+}
+
+#[test]
+fn test_parse_pnpm_workspace() {
+    let patterns = parse_pnpm_workspace(
+        "
+# all packages
+packages:
+  - 'packages/*'
+  - \"apps/**\" # nested apps
+  - tools/cli
+  - '!**/test/**'
+catalog:
+  react: ^18.0.0
+",
+    );
+    assert_eq!(
+        patterns,
+        vec!["packages/*", "apps/**", "tools/cli", "!**/test/**"]
+    );
+
+    assert_eq!(
+        parse_pnpm_workspace("packages: ['apps/*', \"packages/*\"]\n"),
+        vec!["apps/*", "packages/*"]
+    );
+}
+
+#[test]
+fn test_find_workspace_packages() {
+    let dir = crate::fixture::TestDir::new("workspace");
+    let root = dir.path();
+
+    let packages = [
+        ("packages/ui", "@acme/ui"),
+        ("packages/utils", "@acme/utils"),
+        ("packages/ui-test", "@acme/ui-test"),
+        ("apps/web", "web"),
+        ("apps/web/node_modules/dep", "dep"),
+        ("tools/unlisted", "unlisted"),
+    ];
+    for (package_dir, name) in packages {
+        dir.write(
+            &format!("{}/package.json", package_dir),
+            &format!("{{ \"name\": \"{}\" }}", name),
+        );
+    }
+    dir.write(
+        "package.json",
+        r#"{ "private": true, "workspaces": ["packages/*", "apps/**", "!packages/*-test"] }"#,
+    );
+
+    let found = find_workspace_packages(root);
+    let names: Vec<&str> = found.iter().filter_map(PackageJson::name).collect();
+    assert_eq!(names, vec!["web", "@acme/ui", "@acme/utils"]);
+
+    assert_eq!(
+        package_of(&found, &root.join("packages/ui/src/Button.tsx")).and_then(PackageJson::name),
+        Some("@acme/ui")
+    );
+    assert!(package_of(&found, &root.join("tools/unlisted/index.ts")).is_none());
+}